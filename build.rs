@@ -0,0 +1,74 @@
+//! Generates one test per example file in `tests/examples`, included by `tests/examples.rs`
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const EXAMPLES_DIR: &str = "tests/examples";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", EXAMPLES_DIR);
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let examples_dir = Path::new(&manifest_dir).join(EXAMPLES_DIR);
+
+    let mut tests = String::new();
+    for (day, day_dir) in sorted_entries(&examples_dir) {
+        if !day_dir.is_dir() {
+            continue;
+        }
+        println!("cargo:rerun-if-changed={}", day_dir.display());
+        let day_number: u8 = day
+            .strip_prefix("day")
+            .and_then(|number| number.parse().ok())
+            .unwrap_or_else(|| panic!("{}: expected a directory named dayN", day_dir.display()));
+        for (name, path) in sorted_entries(&day_dir) {
+            let Some(stem) = name.strip_suffix(".txt") else {
+                continue;
+            };
+            println!("cargo:rerun-if-changed={}", path.display());
+            writeln!(
+                tests,
+                "#[test]\nfn day{:02}_{}() {{\n    check_example({}, {:?});\n}}\n",
+                day_number,
+                identifier(stem),
+                day_number,
+                path.display().to_string()
+            )
+            .unwrap();
+        }
+    }
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("examples.rs"), tests).unwrap();
+}
+
+/// The entries of `dir` as (file name, path) pairs, sorted by name, or none if it doesn't exist
+fn sorted_entries(dir: &Path) -> Vec<(String, std::path::PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<_> = entries
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (
+                entry.file_name().to_string_lossy().into_owned(),
+                entry.path(),
+            )
+        })
+        .collect();
+    entries.sort();
+    entries
+}
+
+/// Turn a file name into a valid function name
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
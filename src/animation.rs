@@ -0,0 +1,179 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::image::Rgb;
+use crate::render::Render;
+use crate::vec2d::Vec2d;
+
+/// Moves the cursor to the top left of the terminal and clears it, so each frame of a replay is
+/// drawn over the previous one
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// A hook that a step-by-step simulation calls with its state after every step. Any closure
+/// taking the state is an observer, and `()` is an observer that ignores every step.
+pub trait Observer<S: ?Sized> {
+    fn observe(&mut self, state: &S);
+}
+
+impl<S: ?Sized, F: FnMut(&S)> Observer<S> for F {
+    fn observe(&mut self, state: &S) {
+        self(state)
+    }
+}
+
+impl<S: ?Sized> Observer<S> for () {
+    fn observe(&mut self, _state: &S) {}
+}
+
+/// The frames recorded from a simulation, each a snapshot of a grid
+#[derive(Clone, Debug, Default)]
+pub struct Animation<T> {
+    frames: Vec<Vec2d<T>>,
+}
+
+impl<T> Animation<T> {
+    pub fn new() -> Animation<T> {
+        Animation { frames: Vec::new() }
+    }
+
+    /// An observer that records a frame of every step, taken from the state by `capture`
+    pub fn recorder<'a, S: ?Sized>(
+        &'a mut self,
+        mut capture: impl FnMut(&S) -> Vec2d<T> + 'a,
+    ) -> impl Observer<S> + 'a {
+        move |state: &S| self.push(capture(state))
+    }
+
+    pub fn push(&mut self, frame: Vec2d<T>) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[Vec2d<T>] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Write every frame as a PPM image in `dir`, numbered from `frame_0000.ppm` so that the
+    /// files sort in order, and return the paths written
+    pub fn write_images(
+        &self,
+        dir: impl AsRef<Path>,
+        scale: usize,
+        color: impl Fn(&T) -> Rgb,
+    ) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        self.frames
+            .iter()
+            .enumerate()
+            .map(|(index, frame)| {
+                let path = dir.join(format!("frame_{:04}.ppm", index));
+                frame.save_ppm(&path, scale, &color)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Write every frame as drawn by `render`, each preceded by an escape code that clears the
+    /// terminal and a line with the frame number. Printing the result with `cat` or viewing it
+    /// with `less -R` replays the simulation.
+    pub fn write_replay<'a>(
+        &'a self,
+        out: &mut impl Write,
+        render: impl Fn(&'a Vec2d<T>) -> Render<'a, T>,
+    ) -> io::Result<()> {
+        for (index, frame) in self.frames.iter().enumerate() {
+            write!(out, "{}frame {}\n{}", CLEAR_SCREEN, index, render(frame))?;
+        }
+        Ok(())
+    }
+
+    /// Save the replay written by [`Animation::write_replay`] at `path`
+    pub fn save_replay<'a>(
+        &'a self,
+        path: impl AsRef<Path>,
+        render: impl Fn(&'a Vec2d<T>) -> Render<'a, T>,
+    ) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_replay(&mut out, render)?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs;
+
+    use crate::animation::{Animation, Observer};
+    use crate::vec2d::Vec2d;
+
+    /// A toy simulation that fills one more cell of a row on each step
+    fn fill_row(len: usize, observer: &mut impl Observer<Vec2d<bool>>) {
+        let mut grid = Vec2d::with_shape_and_value(1, len, false);
+        for col in 0..len {
+            *grid.get_mut(0, col).unwrap() = true;
+            observer.observe(&grid);
+        }
+    }
+
+    #[test]
+    fn test_record_frames() {
+        let mut animation = Animation::new();
+        fill_row(
+            3,
+            &mut animation.recorder(|grid: &Vec2d<bool>| grid.clone()),
+        );
+        assert_eq!(animation.len(), 3);
+        assert_eq!(animation.frames()[1].as_slice(), &[true, true, false]);
+
+        let mut steps = 0;
+        fill_row(3, &mut |_: &Vec2d<bool>| steps += 1);
+        assert_eq!(steps, 3);
+        fill_row(3, &mut ());
+    }
+
+    #[test]
+    fn test_write_replay() {
+        let mut animation = Animation::new();
+        fill_row(
+            2,
+            &mut animation.recorder(|grid: &Vec2d<bool>| grid.clone()),
+        );
+        let mut out = Vec::new();
+        animation
+            .write_replay(&mut out, |frame| {
+                frame.render(|&full| if full { '#' } else { '.' })
+            })
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[H\x1b[2Jframe 0\n#.\n\x1b[H\x1b[2Jframe 1\n##\n"
+        );
+    }
+
+    #[test]
+    fn test_write_images() {
+        let mut animation = Animation::new();
+        fill_row(
+            2,
+            &mut animation.recorder(|grid: &Vec2d<bool>| grid.clone()),
+        );
+        let dir = env::temp_dir().join(format!("aoc-animation-{}", std::process::id()));
+        let paths = animation
+            .write_images(&dir, 1, |&full| if full { [255; 3] } else { [0; 3] })
+            .unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[1].ends_with("frame_0001.ppm"));
+        assert!(fs::read(&paths[1]).unwrap().starts_with(b"P6\n2 1\n255\n"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
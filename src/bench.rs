@@ -0,0 +1,142 @@
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+use crate::common::{json_string, Answer};
+
+/// Summary statistics over the timings of repeated runs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    /// Compute the statistics of `samples`, returning `None` if there are none
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / sorted.len() as f64;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / sorted.len() as f64;
+        Some(Stats {
+            runs: sorted.len(),
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Call `run` `warm_up` times without timing it, then time it for `runs` iterations
+pub fn measure<T>(warm_up: usize, runs: NonZeroUsize, mut run: impl FnMut() -> T) -> Stats {
+    for _ in 0..warm_up {
+        std::hint::black_box(run());
+    }
+    let samples: Vec<_> = (0..runs.get())
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(run());
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(&samples).expect("there is at least one run")
+}
+
+/// The benchmark of one part of a day's puzzle
+pub struct BenchResult {
+    pub day: u8,
+    pub part: u8,
+    pub title: &'static str,
+    pub answer: Answer,
+    pub stats: Stats,
+}
+
+impl BenchResult {
+    /// Serialize the result as a JSON object, with timings in nanoseconds
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"part\":{},\"title\":{},\"answer\":{},\"runs\":{},\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"std_dev_ns\":{}}}",
+            self.day,
+            self.part,
+            json_string(self.title),
+            self.answer.to_json(),
+            self.stats.runs,
+            self.stats.min.as_nanos(),
+            self.stats.median.as_nanos(),
+            self.stats.mean.as_nanos(),
+            self.stats.std_dev.as_nanos()
+        )
+    }
+}
+
+/// Serialize the results as a JSON array
+pub fn to_json(results: &[BenchResult]) -> String {
+    format!(
+        "[{}]",
+        results
+            .iter()
+            .map(BenchResult::to_json)
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroUsize;
+    use std::time::Duration;
+
+    use crate::bench::{measure, to_json, BenchResult, Stats};
+    use crate::common::Answer;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean.as_micros(), 2500);
+        assert_eq!(stats.std_dev.as_micros(), 1118);
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_measure_runs_warm_up_and_samples() {
+        let mut calls = 0;
+        let stats = measure(2, NonZeroUsize::new(3).unwrap(), || calls += 1);
+        assert_eq!(calls, 5);
+        assert_eq!(stats.runs, 3);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let result = BenchResult {
+            day: 1,
+            part: 2,
+            title: "Trebuchet?!",
+            answer: Answer::from(281u64),
+            stats: Stats::from_samples(&[Duration::from_nanos(10)]).unwrap(),
+        };
+        assert_eq!(
+            to_json(&[result]),
+            r#"[{"day":1,"part":2,"title":"Trebuchet?!","answer":281,"runs":1,"min_ns":10,"median_ns":10,"mean_ns":10,"std_dev_ns":0}]"#
+        );
+    }
+}
@@ -1,102 +1,432 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
 use structopt::StructOpt;
 
-use advent_of_code_2023_solutions::common::Solution;
-use advent_of_code_2023_solutions::day1::{Day1, Day1P2};
-use advent_of_code_2023_solutions::day10::{Day10, Day10P2};
-use advent_of_code_2023_solutions::day11::{Day11, Day11P2};
-use advent_of_code_2023_solutions::day12::{Day12, Day12P2};
-use advent_of_code_2023_solutions::day13::{Day13, Day13P2};
-use advent_of_code_2023_solutions::day14::{Day14, Day14P2};
-use advent_of_code_2023_solutions::day15::{Day15, Day15P2};
-use advent_of_code_2023_solutions::day16::{Day16, Day16P2};
-use advent_of_code_2023_solutions::day17::{Day17, Day17P2};
-use advent_of_code_2023_solutions::day18::{Day18, Day18P2};
-use advent_of_code_2023_solutions::day19::{Day19, Day19P2};
-use advent_of_code_2023_solutions::day2::{Day2, Day2P2};
-use advent_of_code_2023_solutions::day20::{Day20, Day20P2};
-use advent_of_code_2023_solutions::day21::{Day21, Day21P2};
-use advent_of_code_2023_solutions::day22::{Day22, Day22P2};
-use advent_of_code_2023_solutions::day23::{Day23, Day23P2};
-use advent_of_code_2023_solutions::day24::{Day24, Day24P2};
-use advent_of_code_2023_solutions::day25::Day25;
-use advent_of_code_2023_solutions::day3::{Day3, Day3P2};
-use advent_of_code_2023_solutions::day4::{Day4, Day4P2};
-use advent_of_code_2023_solutions::day5::{Day5, Day5P2};
-use advent_of_code_2023_solutions::day6::{Day6, Day6P2};
-use advent_of_code_2023_solutions::day7::{Day7, Day7P2};
-use advent_of_code_2023_solutions::day8::{Day8, Day8P2};
-use advent_of_code_2023_solutions::day9::{Day9, Day9P2};
-
-fn main() {
-    let cli = Cli::from_args();
-
-    let file = File::open(cli.input).expect("Could not read input");
-    let reader = BufReader::new(file);
-    let lines = reader
-        .lines()
-        .map(|result| result.expect("Could not read line from input file"));
-    let result = match (cli.day, cli.part) {
-        (1, 1) => Day1::solve(lines),
-        (1, 2) => Day1P2::solve(lines),
-        (2, 1) => Day2::solve(lines),
-        (2, 2) => Day2P2::solve(lines),
-        (3, 1) => Day3::solve(lines),
-        (3, 2) => Day3P2::solve(lines),
-        (4, 1) => Day4::solve(lines),
-        (4, 2) => Day4P2::solve(lines),
-        (5, 1) => Day5::solve(lines),
-        (5, 2) => Day5P2::solve(lines),
-        (6, 1) => Day6::solve(lines),
-        (6, 2) => Day6P2::solve(lines),
-        (7, 1) => Day7::solve(lines),
-        (7, 2) => Day7P2::solve(lines),
-        (8, 1) => Day8::solve(lines),
-        (8, 2) => Day8P2::solve(lines),
-        (9, 1) => Day9::solve(lines),
-        (9, 2) => Day9P2::solve(lines),
-        (10, 1) => Day10::solve(lines),
-        (10, 2) => Day10P2::solve(lines),
-        (11, 1) => Day11::solve(lines),
-        (11, 2) => Day11P2::solve(lines),
-        (12, 1) => Day12::solve(lines),
-        (12, 2) => Day12P2::solve(lines),
-        (13, 1) => Day13::solve(lines),
-        (13, 2) => Day13P2::solve(lines),
-        (14, 1) => Day14::solve(lines),
-        (14, 2) => Day14P2::solve(lines),
-        (15, 1) => Day15::solve(lines),
-        (15, 2) => Day15P2::solve(lines),
-        (16, 1) => Day16::solve(lines),
-        (16, 2) => Day16P2::solve(lines),
-        (17, 1) => Day17::solve(lines),
-        (17, 2) => Day17P2::solve(lines),
-        (18, 1) => Day18::solve(lines),
-        (18, 2) => Day18P2::solve(lines),
-        (19, 1) => Day19::solve(lines),
-        (19, 2) => Day19P2::solve(lines),
-        (20, 1) => Day20::solve(lines),
-        (20, 2) => Day20P2::solve(lines),
-        (21, 1) => Day21::solve(lines),
-        (21, 2) => Day21P2::solve(lines),
-        (22, 1) => Day22::solve(lines),
-        (22, 2) => Day22P2::solve(lines),
-        (23, 1) => Day23::solve(lines),
-        (23, 2) => Day23P2::solve(lines),
-        (24, 1) => Day24::solve(lines),
-        (24, 2) => Day24P2::solve(lines),
-        (25, 1) => Day25::solve(lines),
-        _ => unimplemented!(),
-    };
-    println!("{}", result)
+use advent_of_code_2023_solutions::bench;
+use advent_of_code_2023_solutions::bench::BenchResult;
+use advent_of_code_2023_solutions::common::Answer;
+use advent_of_code_2023_solutions::expected::parse_expected_answers;
+use advent_of_code_2023_solutions::input;
+use advent_of_code_2023_solutions::params::Params;
+use advent_of_code_2023_solutions::registry;
+use advent_of_code_2023_solutions::registry::{ParsedInput, Registration};
+
+fn main() -> ExitCode {
+    match Cli::from_args() {
+        Cli::Solve {
+            day,
+            part,
+            input,
+            inputs,
+            params,
+        } => solve(day, part, input, &inputs.path, &params),
+        Cli::List => {
+            list();
+            ExitCode::SUCCESS
+        }
+        Cli::RunAll { inputs } => run_all(&inputs.path),
+        Cli::Verify { answers, inputs } => verify(&answers, &inputs.path),
+        Cli::Bench(options) => bench(options),
+    }
 }
 
-#[derive(StructOpt)]
-struct Cli {
+fn solve(
     day: u8,
     part: u8,
-    input: PathBuf,
+    input: Option<PathBuf>,
+    inputs: &Path,
+    assignments: &[String],
+) -> ExitCode {
+    let registration = match registry::find(day, part) {
+        Ok(registration) => registration,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let params = match parse_params(registration, assignments) {
+        Ok(params) => params,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let input = match input.map_or_else(|| input::find(inputs, day), Ok) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let lines = match read_lines(&input) {
+        Ok(lines) => lines,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    match registration.solve(&lines, &params) {
+        Ok(answer) => {
+            println!("{}", answer);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse `--param name=value` arguments, checking that the solution uses each parameter
+fn parse_params(registration: &Registration, assignments: &[String]) -> Result<Params, String> {
+    let mut params = Params::default();
+    for assignment in assignments {
+        params
+            .set_from_str(assignment)
+            .map_err(|err| format!("{:#}", err))?;
+    }
+    registration
+        .check_params(&params)
+        .map_err(|err| err.to_string())?;
+    Ok(params)
+}
+
+fn list() {
+    for registration in registry::all() {
+        println!(
+            "day {:>2} part {}  {}",
+            registration.day(),
+            registration.part(),
+            registration.title()
+        );
+        for param in registration.params() {
+            println!(
+                "    --param {}={}  {}",
+                param.name, param.default, param.description
+            );
+        }
+    }
+}
+
+/// Run every registered solution against the inputs in `inputs` and print a table of the results
+fn run_all(inputs: &Path) -> ExitCode {
+    println!(
+        "{:>3} {:>4}  {:<20} {:>10} {:>10}  status",
+        "day", "part", "answer", "parse", "solve"
+    );
+    let mut runner = Runner::new(inputs);
+    let mut failed = false;
+    for registration in registry::all() {
+        let (answer, parse_time, solve_time, status) = match runner.run(registration) {
+            Outcome::MissingInput => (String::new(), None, None, "missing input".to_string()),
+            Outcome::Failed(err) => {
+                failed = true;
+                (String::new(), None, None, format!("error: {}", err))
+            }
+            Outcome::Solved {
+                answer,
+                parse_time,
+                solve_time,
+            } => {
+                let status = if answer.is_computed() {
+                    "ok"
+                } else {
+                    "not computed"
+                };
+                (
+                    answer.to_string(),
+                    Some(parse_time),
+                    Some(solve_time),
+                    status.to_string(),
+                )
+            }
+        };
+        println!(
+            "{:>3} {:>4}  {:<20} {:>10} {:>10}  {}",
+            registration.day(),
+            registration.part(),
+            answer,
+            parse_time.map(format_duration).unwrap_or_default(),
+            solve_time.map(format_duration).unwrap_or_default(),
+            status
+        );
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Check the solutions against the answers in the `answers` file, printing the result of each
+fn verify(answers: &Path, inputs: &Path) -> ExitCode {
+    let expected_answers = match read_lines(answers)
+        .and_then(|lines| parse_expected_answers(lines.iter()).map_err(|err| err.to_string()))
+    {
+        Ok(expected_answers) => expected_answers,
+        Err(err) => {
+            eprintln!("error: {}: {}", answers.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut runner = Runner::new(inputs);
+    let (mut passed, mut failed) = (0, 0);
+    for expected in expected_answers {
+        let result = registry::find(expected.day, expected.part)
+            .map_err(|err| err.to_string())
+            .and_then(|registration| match runner.run(registration) {
+                Outcome::MissingInput => Err("missing input".to_string()),
+                Outcome::Failed(err) => Err(err),
+                Outcome::Solved { answer, .. } => Ok(answer),
+            });
+        let prefix = format!("day {:>2} part {}", expected.day, expected.part);
+        match result {
+            Ok(answer) if expected.matches(&answer) => {
+                passed += 1;
+                println!("{}: pass", prefix);
+            }
+            Ok(answer) => {
+                failed += 1;
+                println!("{}: FAIL", prefix);
+                println!("  - expected: {}", expected.answer);
+                println!("  + actual:   {}", answer);
+            }
+            Err(err) => {
+                failed += 1;
+                println!("{}: FAIL ({})", prefix, err);
+            }
+        }
+    }
+    println!("{} passed, {} failed", passed, failed);
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Time the registered solutions matching `day` and `part`, printing a table or JSON
+fn bench(options: BenchOptions) -> ExitCode {
+    let mut results = Vec::new();
+    let mut failed = false;
+    let registrations = registry::all().into_iter().filter(|registration| {
+        options.day.is_none_or(|day| registration.day() == day)
+            && options.part.is_none_or(|part| registration.part() == part)
+    });
+    for registration in registrations {
+        let (day, part) = (registration.day(), registration.part());
+        let Some(input) = find_input(&options.inputs.path, day) else {
+            eprintln!("day {} part {}: skipped, missing input", day, part);
+            continue;
+        };
+        // Solve once up front so that errors are reported rather than timed
+        let solved = read_lines(&input).and_then(|lines| {
+            let answer = registration
+                .solve(&lines, &Params::default())
+                .map_err(|err| err.to_string())?;
+            Ok((lines, answer))
+        });
+        let (lines, answer) = match solved {
+            Ok(solved) => solved,
+            Err(err) => {
+                failed = true;
+                eprintln!("day {} part {}: error: {}", day, part, err);
+                continue;
+            }
+        };
+        let stats = bench::measure(options.warm_up, options.runs, || {
+            registration.solve(&lines, &Params::default())
+        });
+        results.push(BenchResult {
+            day,
+            part,
+            title: registration.title(),
+            answer,
+            stats,
+        });
+    }
+    if options.json {
+        println!("{}", bench::to_json(&results));
+    } else {
+        println!(
+            "{:>3} {:>4} {:>5} {:>10} {:>10} {:>10} {:>10}",
+            "day", "part", "runs", "min", "median", "mean", "std dev"
+        );
+        for result in &results {
+            println!(
+                "{:>3} {:>4} {:>5} {:>10} {:>10} {:>10} {:>10}",
+                result.day,
+                result.part,
+                result.stats.runs,
+                format_duration(result.stats.min),
+                format_duration(result.stats.median),
+                format_duration(result.stats.mean),
+                format_duration(result.stats.std_dev)
+            );
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+enum Outcome {
+    MissingInput,
+    Failed(String),
+    Solved {
+        answer: Answer,
+        parse_time: Duration,
+        solve_time: Duration,
+    },
+}
+
+/// Runs solutions against the inputs in a directory, reusing the parsed input when both parts of
+/// a day are run one after the other
+struct Runner<'a> {
+    inputs: &'a Path,
+    parsed: Option<(u8, ParsedInput, Duration)>,
+}
+
+impl<'a> Runner<'a> {
+    fn new(inputs: &'a Path) -> Runner<'a> {
+        Runner {
+            inputs,
+            parsed: None,
+        }
+    }
+
+    /// Solve the puzzle with the input for its day, timing parsing and solving
+    fn run(&mut self, registration: &Registration) -> Outcome {
+        let (parsed, parse_time) = match self.parse(registration) {
+            Ok(parsed) => parsed,
+            Err(outcome) => return outcome,
+        };
+        let start = Instant::now();
+        let result = registration.answer(parsed, &Params::default());
+        let solve_time = start.elapsed();
+        match result {
+            Ok(answer) => Outcome::Solved {
+                answer,
+                parse_time,
+                solve_time,
+            },
+            Err(err) => Outcome::Failed(err.to_string()),
+        }
+    }
+
+    /// The parsed input for the day of the puzzle and the time parsing took, parsing it unless
+    /// it was parsed for the previous run
+    fn parse(&mut self, registration: &Registration) -> Result<(&ParsedInput, Duration), Outcome> {
+        let day = registration.day();
+        let previous = self
+            .parsed
+            .take()
+            .filter(|(parsed_day, ..)| *parsed_day == day);
+        let (_, parsed, parse_time) = match previous {
+            Some(previous) => self.parsed.insert(previous),
+            None => {
+                let input = find_input(self.inputs, day).ok_or(Outcome::MissingInput)?;
+                let lines = read_lines(&input).map_err(Outcome::Failed)?;
+                let start = Instant::now();
+                let parsed = registration
+                    .parse(&lines)
+                    .map_err(|err| Outcome::Failed(err.to_string()))?;
+                self.parsed.insert((day, parsed, start.elapsed()))
+            }
+        };
+        Ok((parsed, *parse_time))
+    }
+}
+
+fn find_input(inputs: &Path, day: u8) -> Option<PathBuf> {
+    input::find(inputs, day).ok()
+}
+
+fn read_lines(input: &Path) -> Result<Vec<String>, String> {
+    input::read_lines(input).map_err(|err| format!("{:#}", err))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1e6;
+    if micros < 1e3 {
+        format!("{:.1}µs", micros)
+    } else if micros < 1e6 {
+        format!("{:.1}ms", micros / 1e3)
+    } else {
+        format!("{:.2}s", micros / 1e6)
+    }
+}
+
+#[derive(StructOpt)]
+enum Cli {
+    /// Solve one part of a day's puzzle
+    Solve {
+        day: u8,
+        part: u8,
+        /// The input file, or `-` to read stdin. Defaults to the day's file in the inputs
+        /// directory.
+        #[structopt(parse(from_os_str))]
+        input: Option<PathBuf>,
+        #[structopt(flatten)]
+        inputs: InputsDir,
+        /// Override a parameter of the solution, as `name=value`. Use `list` to see the
+        /// parameters of each solution.
+        #[structopt(long = "param", number_of_values = 1)]
+        params: Vec<String>,
+    },
+    /// List the days and parts that have solutions
+    List,
+    /// Run every day and part and print a table of the results
+    RunAll {
+        #[structopt(flatten)]
+        inputs: InputsDir,
+    },
+    /// Check the solutions against a file of known answers
+    Verify {
+        /// File with one `<day> <part> <answer>` line per known answer
+        #[structopt(long, default_value = "answers.txt")]
+        answers: PathBuf,
+        #[structopt(flatten)]
+        inputs: InputsDir,
+    },
+    /// Time the solutions over repeated runs
+    Bench(BenchOptions),
+}
+
+#[derive(StructOpt)]
+struct BenchOptions {
+    /// Only benchmark this day
+    #[structopt(long)]
+    day: Option<u8>,
+    /// Only benchmark this part
+    #[structopt(long)]
+    part: Option<u8>,
+    #[structopt(flatten)]
+    inputs: InputsDir,
+    /// Number of untimed runs before measuring
+    #[structopt(long, default_value = "3")]
+    warm_up: usize,
+    /// Number of timed runs, at least 1
+    #[structopt(long, default_value = "10")]
+    runs: NonZeroUsize,
+    /// Print the results as JSON instead of a table
+    #[structopt(long)]
+    json: bool,
+}
+
+#[derive(StructOpt)]
+struct InputsDir {
+    /// Directory containing the inputs, named `dayNN.txt` or `dayN.txt`
+    #[structopt(
+        long = "inputs",
+        env = input::INPUTS_ENV,
+        default_value = input::DEFAULT_INPUTS,
+        parse(from_os_str)
+    )]
+    path: PathBuf,
 }
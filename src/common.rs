@@ -1,3 +1,527 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+
+use num::BigInt;
+
+use crate::params::{Param, Params};
+use crate::vec2d::{ShapeError, Vec2d};
+
+/// A day's puzzle, solved by parsing the input once and then computing each part from the parsed
+/// [`Puzzle::Input`]
+pub trait Puzzle {
+    /// The day of the puzzle, from 1 to 25
+    const DAY: u8;
+    /// The title of the day's puzzle
+    const TITLE: &'static str;
+
+    /// The parameters that the parts read from [`Params`]
+    const PARAMS: &'static [Param] = &[];
+
+    type Input: 'static;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError>;
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Answer, SolveError>;
+
+    /// Solve the second part. Puzzles with only one part, like the last day's, keep this default
+    /// and are registered without a [`Part2`].
+    fn part2(_input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(Answer::NotComputed)
+    }
+}
+
+/// The second part of a [`Puzzle`], as a [`Solution`]. The puzzle itself solves the first part.
+pub struct Part2<P: Puzzle>(PhantomData<P>);
+
+/// One part of a day's puzzle
 pub trait Solution {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String;
+    /// The day of the puzzle, from 1 to 25
+    const DAY: u8;
+    /// The part of the puzzle, either 1 or 2
+    const PART: u8;
+    /// The title of the day's puzzle
+    const TITLE: &'static str;
+    /// The parameters of the day's puzzle, including those used by the other part
+    const PARAMS: &'static [Param];
+
+    type Input: 'static;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError>;
+
+    /// Compute the answer from the parsed input
+    fn answer(input: &Self::Input, params: &Params) -> Result<Answer, SolveError>;
+
+    /// Solve the puzzle with the default parameters
+    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Answer, SolveError> {
+        Self::answer(&Self::parse(lines)?, &Params::default())
+    }
+}
+
+impl<P: Puzzle> Solution for P {
+    const DAY: u8 = P::DAY;
+    const PART: u8 = 1;
+    const TITLE: &'static str = P::TITLE;
+    const PARAMS: &'static [Param] = P::PARAMS;
+
+    type Input = P::Input;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        P::parse(lines)
+    }
+
+    fn answer(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        P::part1(input, params)
+    }
+}
+
+impl<P: Puzzle> Solution for Part2<P> {
+    const DAY: u8 = P::DAY;
+    const PART: u8 = 2;
+    const TITLE: &'static str = P::TITLE;
+    const PARAMS: &'static [Param] = P::PARAMS;
+
+    type Input = P::Input;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        P::parse(lines)
+    }
+
+    fn answer(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        P::part2(input, params)
+    }
+}
+
+/// The result of solving a puzzle
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Int(i64),
+    Unsigned(u64),
+    BigInt(BigInt),
+    Text(String),
+    /// The solution does not compute an answer for this input
+    NotComputed,
+}
+
+impl Answer {
+    /// Returns false for answers that should not be reported, i.e. [`Answer::NotComputed`] and
+    /// empty text
+    pub fn is_computed(&self) -> bool {
+        match self {
+            Answer::NotComputed => false,
+            Answer::Text(text) => !text.is_empty(),
+            _ => true,
+        }
+    }
+
+    /// The value of a numeric answer, regardless of which numeric variant holds it
+    pub fn as_big_int(&self) -> Option<BigInt> {
+        match self {
+            Answer::Int(value) => Some(BigInt::from(*value)),
+            Answer::Unsigned(value) => Some(BigInt::from(*value)),
+            Answer::BigInt(value) => Some(value.clone()),
+            Answer::Text(_) | Answer::NotComputed => None,
+        }
+    }
+
+    /// Serialize the answer as a JSON value. Numbers are written as JSON numbers, text as a JSON
+    /// string and a missing answer as null.
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Int(_) | Answer::Unsigned(_) | Answer::BigInt(_) => self.to_string(),
+            Answer::Text(text) => json_string(text),
+            Answer::NotComputed => "null".to_string(),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::Unsigned(value) => write!(f, "{}", value),
+            Answer::BigInt(value) => write!(f, "{}", value),
+            Answer::Text(text) => f.write_str(text),
+            Answer::NotComputed => f.write_str("(not computed)"),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::NotComputed, Answer::NotComputed) => true,
+            (a, b) => match (a.as_big_int(), b.as_big_int()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialEq<str> for Answer {
+    /// Compares the answer as it is displayed, except that [`Answer::NotComputed`] never equals a
+    /// string, not even its placeholder text
+    fn eq(&self, other: &str) -> bool {
+        !matches!(self, Answer::NotComputed) && self.to_string().as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialOrd for Answer {
+    /// Numeric answers are ordered by value and text answers lexicographically. Other
+    /// combinations are not comparable.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => Some(a.cmp(b)),
+            (Answer::NotComputed, Answer::NotComputed) => Some(Ordering::Equal),
+            (a, b) => match (a.as_big_int(), b.as_big_int()) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => None,
+            },
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Self {
+                    Answer::$variant(value as $target)
+                }
+            }
+        )+
+    };
+}
+
+answer_from!(Int, i64, i64, i32);
+answer_from!(Unsigned, u64, u64, u32, usize);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        u64::try_from(value)
+            .map(Answer::Unsigned)
+            .unwrap_or_else(|_| Answer::BigInt(BigInt::from(value)))
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Answer::BigInt(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// An error raised while solving a puzzle, describing where in the input things went wrong
+#[derive(Debug)]
+pub struct SolveError {
+    day: Option<u8>,
+    line: Option<usize>,
+    step: &'static str,
+    source: anyhow::Error,
+}
+
+impl SolveError {
+    pub fn new(step: &'static str, source: impl Into<anyhow::Error>) -> SolveError {
+        SolveError {
+            day: None,
+            line: None,
+            step,
+            source: source.into(),
+        }
+    }
+
+    pub fn msg(step: &'static str, message: impl Display) -> SolveError {
+        SolveError::new(step, anyhow::Error::msg(message.to_string()))
+    }
+
+    /// Attach the 1-based line number that the error refers to
+    pub fn on_line(self, line: usize) -> SolveError {
+        SolveError {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    /// Shift the line number by `offset`, for errors raised while parsing a block of the input
+    pub fn offset_line(self, offset: usize) -> SolveError {
+        SolveError {
+            line: self.line.map(|line| line + offset),
+            ..self
+        }
+    }
+
+    pub fn with_day(self, day: u8) -> SolveError {
+        SolveError {
+            day: Some(day),
+            ..self
+        }
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn step(&self) -> &'static str {
+        self.step
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}: ", day)?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "failed to {}: {:#}", self.step, self.source)
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Parse every line with `parse`, reporting the line number of the first line that fails
+pub fn parse_lines<T, F>(
+    lines: impl Iterator<Item = impl AsRef<str>>,
+    step: &'static str,
+    mut parse: F,
+) -> Result<Vec<T>, SolveError>
+where
+    F: FnMut(&str) -> anyhow::Result<T>,
+{
+    lines
+        .enumerate()
+        .map(|(index, line)| {
+            parse(line.as_ref()).map_err(|err| SolveError::new(step, err).on_line(index + 1))
+        })
+        .collect()
+}
+
+/// Parse a grid of characters, converting each one with `parse`. Errors report the line and
+/// column of the first character that fails, or the first line whose length differs from the
+/// first line.
+pub fn parse_grid<T, F>(
+    lines: impl Iterator<Item = impl AsRef<str>>,
+    step: &'static str,
+    parse: F,
+) -> Result<Vec2d<T>, SolveError>
+where
+    F: Fn(char) -> anyhow::Result<T>,
+{
+    let chars = Vec2d::from_lines(lines).map_err(|err| match err {
+        ShapeError::Empty => SolveError::msg(step, "the input is empty"),
+        ShapeError::EmptyRow { row } => SolveError::msg(step, "the line is empty").on_line(row + 1),
+        ShapeError::Ragged { row, len, expected } => SolveError::msg(
+            step,
+            format!(
+                "the line has length {}, but the first line has length {}",
+                len, expected
+            ),
+        )
+        .on_line(row + 1),
+    })?;
+    chars.try_map(parse).map_err(|err| {
+        let line = err.coords.row + 1;
+        let message = format!(
+            "unknown tile {:?} in column {}",
+            err.tile,
+            err.coords.col + 1
+        );
+        SolveError::new(step, err.source.context(message)).on_line(line)
+    })
+}
+
+/// Split the input into blocks separated by blank lines. Each block is returned along with the
+/// number of lines that precede it, for use with [`SolveError::offset_line`].
+pub fn split_blocks(lines: impl Iterator<Item = impl AsRef<str>>) -> Vec<(usize, Vec<String>)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(usize, Vec<String>)> = None;
+    for (index, line) in lines.enumerate() {
+        let line = line.as_ref();
+        if line.is_empty() {
+            blocks.extend(current.take());
+        } else {
+            current
+                .get_or_insert_with(|| (index, Vec::new()))
+                .1
+                .push(line.to_string());
+        }
+    }
+    blocks.extend(current);
+    blocks
+}
+
+#[cfg(test)]
+mod test {
+    use num::BigInt;
+
+    use crate::common::{
+        parse_grid, parse_lines, split_blocks, Answer, Part2, Puzzle, Solution, SolveError,
+    };
+    use crate::params::Params;
+
+    enum Sums {}
+
+    impl Puzzle for Sums {
+        const DAY: u8 = 1;
+        const TITLE: &'static str = "Sums";
+
+        type Input = Vec<u64>;
+
+        fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+            parse_lines(lines, "parse number", |line| Ok(line.parse()?))
+        }
+
+        fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+            Ok(input.iter().sum::<u64>().into())
+        }
+
+        fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+            Ok(input.iter().map(|value| value * value).sum::<u64>().into())
+        }
+    }
+
+    #[test]
+    fn test_puzzle_parts() {
+        let input = <Sums as Puzzle>::parse(["1", "2", "3"].iter()).unwrap();
+        let params = Params::default();
+        assert_eq!(<Sums as Solution>::answer(&input, &params).unwrap(), "6");
+        assert_eq!(Part2::<Sums>::answer(&input, &params).unwrap(), "14");
+        assert_eq!(Part2::<Sums>::solve(["4"].iter()).unwrap(), "16");
+        assert_eq!((<Sums as Solution>::PART, Part2::<Sums>::PART), (1, 2));
+    }
+
+    #[test]
+    fn test_answer_compares_numerically() {
+        assert_eq!(Answer::from(42i64), Answer::from(42usize));
+        assert_eq!(Answer::from(42u32), Answer::BigInt(BigInt::from(42)));
+        assert!(Answer::from(-1i64) < Answer::from(0u64));
+        assert!(Answer::from(u128::MAX) > Answer::from(u64::MAX));
+        assert_ne!(Answer::from(42u64), Answer::from("42"));
+        assert_eq!(Answer::from(42u64), "42");
+        assert_eq!(Answer::from(42u64).partial_cmp(&Answer::from("42")), None);
+    }
+
+    #[test]
+    fn test_answer_not_computed() {
+        assert!(!Answer::NotComputed.is_computed());
+        assert!(!Answer::from("").is_computed());
+        assert!(Answer::from(0u64).is_computed());
+        assert_ne!(Answer::NotComputed, "(not computed)");
+    }
+
+    #[test]
+    fn test_answer_equals_displayed_text() {
+        assert_eq!(Answer::from(""), "");
+        assert_eq!(Answer::from(""), Answer::from(""));
+        assert_eq!(Answer::from("ab"), "ab");
+        assert_eq!(Answer::from(-3i64), "-3");
+        assert_ne!(Answer::from(3u64), "03");
+    }
+
+    #[test]
+    fn test_answer_to_json() {
+        assert_eq!(Answer::from(u128::MAX).to_json(), u128::MAX.to_string());
+        assert_eq!(Answer::from(-3i64).to_json(), "-3");
+        assert_eq!(Answer::from("a \"b\"\n").to_json(), r#""a \"b\"\n""#);
+        assert_eq!(Answer::NotComputed.to_json(), "null");
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line() {
+        let err = parse_lines(["1", "2", "x"].iter(), "parse number", |s| {
+            Ok(s.parse::<u32>()?)
+        })
+        .unwrap_err();
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.step(), "parse number");
+        assert_eq!(
+            err.with_day(4).to_string(),
+            "day 4: line 3: failed to parse number: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_reports_position() {
+        let digit = |c: char| {
+            c.to_digit(10)
+                .ok_or_else(|| anyhow::Error::msg("not a digit"))
+        };
+        let grid = parse_grid(["12", "34"].iter(), "parse digit", digit).unwrap();
+        assert_eq!(grid.get(1, 0), Some(&3));
+
+        let err = parse_grid(["12", "3x"].iter(), "parse digit", digit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: failed to parse digit: unknown tile 'x' in column 2: not a digit"
+        );
+        let err = parse_grid(["12", "345"].iter(), "parse digit", digit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: failed to parse digit: the line has length 3, but the first line has length 2"
+        );
+        let err = parse_grid(std::iter::empty::<&str>(), "parse digit", digit).unwrap_err();
+        assert_eq!(err.to_string(), "failed to parse digit: the input is empty");
+    }
+
+    #[test]
+    fn test_split_blocks() {
+        let blocks = split_blocks(["a", "b", "", "c", "", ""].iter());
+        assert_eq!(
+            blocks,
+            vec![
+                (0, vec!["a".to_string(), "b".to_string()]),
+                (3, vec!["c".to_string()])
+            ]
+        );
+        let err = SolveError::msg("parse block", "bad").on_line(1);
+        assert_eq!(err.offset_line(3).line(), Some(4));
+    }
 }
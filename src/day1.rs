@@ -2,23 +2,37 @@ use std::str::FromStr;
 
 use regex::Regex;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day1 {}
 
-pub enum Day1P2 {}
+pub type Day1P2 = Part2<Day1>;
 
-impl Solution for Day1 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|line| get_number(line.as_ref()))
-            .sum::<u32>()
-            .to_string()
+register!(Day1, Day1P2);
+
+impl Puzzle for Day1 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    /// The calibration lines, which are read differently by each part
+    type Input = Vec<String>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Ok(lines.map(|line| line.as_ref().to_string()).collect())
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(
+            parse_lines(input.iter(), "read calibration value", get_number)?
+                .into_iter()
+                .sum::<u32>()
+                .into(),
+        )
     }
-}
 
-impl Solution for Day1P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
         let match_values = [
             "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "one", "two", "three", "four",
             "five", "six", "seven", "eight", "nine", "zero",
@@ -33,10 +47,12 @@ impl Solution for Day1P2 {
         let forward_regex = Regex::from_str(forward_pattern.as_str()).unwrap();
 
         let backward_regex = Regex::from_str(backward_pattern.as_str()).unwrap();
-        lines
-            .map(|line| get_number_part2(&forward_regex, &backward_regex, line.as_ref()))
-            .sum::<u32>()
-            .to_string()
+        Ok(parse_lines(input.iter(), "read calibration value", |line| {
+            get_number_part2(&forward_regex, &backward_regex, line)
+        })?
+        .into_iter()
+        .sum::<u32>()
+        .into())
     }
 }
 
@@ -44,37 +60,43 @@ fn reverse_string(s: &str) -> String {
     s.chars().rev().collect()
 }
 
-fn get_number(line: &str) -> u32 {
+fn get_number(line: &str) -> anyhow::Result<u32> {
     if line.is_empty() {
-        return 0;
+        return Ok(0);
     }
     let first_digit = line
         .chars()
         .find_map(|c| c.to_digit(10))
-        .expect("Did not find first digit");
+        .ok_or_else(|| anyhow::Error::msg("Did not find first digit").context(line.to_string()))?;
     let last_digit = line
         .chars()
         .rev()
         .find_map(|c| c.to_digit(10))
-        .expect("Did not find last digit");
-    10 * first_digit + last_digit
+        .ok_or_else(|| anyhow::Error::msg("Did not find last digit").context(line.to_string()))?;
+    Ok(10 * first_digit + last_digit)
 }
 
-fn get_number_part2(forward_regex: &Regex, backward_regex: &Regex, line: &str) -> u32 {
+fn get_number_part2(
+    forward_regex: &Regex,
+    backward_regex: &Regex,
+    line: &str,
+) -> anyhow::Result<u32> {
     let first = forward_regex
         .find(line)
-        .expect("No substring match found")
+        .ok_or_else(|| anyhow::Error::msg("No substring match found").context(line.to_string()))?
         .as_str();
     let last = reverse_string(
         backward_regex
             .find(reverse_string(line).as_str())
-            .expect("No match for last_regex found")
+            .ok_or_else(|| {
+                anyhow::Error::msg("No match for last_regex found").context(line.to_string())
+            })?
             .as_str(),
     );
 
     let first_digit = to_digit(first);
     let last_digit = to_digit(last.as_str());
-    first_digit * 10 + last_digit
+    Ok(first_digit * 10 + last_digit)
 }
 
 fn to_digit(s: &str) -> u32 {
@@ -96,35 +118,11 @@ fn to_digit(s: &str) -> u32 {
 #[cfg(test)]
 mod test {
     use crate::common::Solution;
-    use crate::day1::{Day1, Day1P2};
-
-    #[test]
-    fn test_day1_example() {
-        const INPUT: &str = r#"
-1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet
-"#;
-        assert_eq!(Day1::solve(INPUT.lines()), "142");
-    }
-
-    #[test]
-    fn test_day1p2_example() {
-        const INPUT: &str = r#"two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen
-"#;
-        assert_eq!(Day1P2::solve(INPUT.lines()), "281")
-    }
+    use crate::day1::Day1P2;
 
     #[test]
     fn test_tricky_day1p2() {
         let input = "oneight";
-        assert_eq!(Day1P2::solve(input.lines()), "18");
+        assert_eq!(Day1P2::solve(input.lines()).unwrap(), "18");
     }
 }
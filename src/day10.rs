@@ -1,25 +1,35 @@
 #![allow(dead_code)]
 
-use crate::common::Solution;
+use std::collections::HashSet;
+
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
+use crate::render::{Color, Render};
 use crate::vec2d::{Cell, Vec2d};
 
 pub enum Day10 {}
 
-impl Solution for Day10 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let matrix = Vec2d::from_lines(lines);
-        let matrix = matrix.map(|c| Tile::from(*c));
-        ((matrix.compute_loop().len() + 1) / 2).to_string()
+pub type Day10P2 = Part2<Day10>;
+
+register!(Day10, Day10P2);
+
+impl Puzzle for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Pipe Maze";
+
+    type Input = Vec2d<Tile>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_tiles(lines)
     }
-}
 
-pub enum Day10P2 {}
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.compute_loop().len().div_ceil(2).into())
+    }
 
-impl Solution for Day10P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let matrix = Vec2d::from_lines(lines);
-        let matrix = matrix.map(|c| Tile::from(*c));
-        let boundary: Vec<_> = matrix.compute_loop();
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let boundary: Vec<_> = input.compute_loop();
 
         let boundary_size = boundary.len();
         let mut vertices: Vec<_> = boundary
@@ -28,7 +38,7 @@ impl Solution for Day10P2 {
             .collect();
 
         // add start so that shoelace formula works
-        vertices.push(matrix.find_start());
+        vertices.push(input.find_start());
 
         // https://en.wikipedia.org/wiki/Shoelace_formula#Triangle_formula
         let shoelace_loop_area = vertices
@@ -42,13 +52,21 @@ impl Solution for Day10P2 {
             / 2f64;
 
         // https://en.wikipedia.org/wiki/Pick%27s_theorem
-        (shoelace_loop_area - (boundary_size as f64 / 2f64) + 1.0)
-            .round()
-            .to_string()
+        let interior = (shoelace_loop_area - (boundary_size as f64 / 2f64) + 1.0).round();
+        Ok((interior as u64).into())
     }
 }
 
-enum Tile {
+/// Parse the grid of pipes, checking that it has a starting tile
+fn parse_tiles(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Vec2d<Tile>, SolveError> {
+    let matrix = parse_grid(lines, "parse pipe", Tile::try_from)?;
+    if !matrix.cells().any(|cell| cell.value().is_start()) {
+        return Err(SolveError::msg("find start", "no S tile in input"));
+    }
+    Ok(matrix)
+}
+
+pub enum Tile {
     NorthSouth,
     EastWest,
     NorthEast,
@@ -59,23 +77,39 @@ enum Tile {
     Start,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Tile::Empty,
-            '|' => Tile::NorthSouth,
-            '-' => Tile::EastWest,
-            'L' => Tile::NorthEast,
-            'J' => Tile::NorthWest,
-            '7' => Tile::SouthWest,
-            'F' => Tile::SouthEast,
-            'S' => Tile::Start,
-            value => panic!("Invalid char received: {}", value),
+            '.' => Ok(Tile::Empty),
+            '|' => Ok(Tile::NorthSouth),
+            '-' => Ok(Tile::EastWest),
+            'L' => Ok(Tile::NorthEast),
+            'J' => Ok(Tile::NorthWest),
+            '7' => Ok(Tile::SouthWest),
+            'F' => Ok(Tile::SouthEast),
+            'S' => Ok(Tile::Start),
+            value => Err(anyhow::Error::msg("Invalid char received").context(value)),
         }
     }
 }
 
 impl Tile {
+    /// The tile drawn with box-drawing characters, which are easier to follow than the input
+    pub fn glyph(&self) -> char {
+        match self {
+            Tile::NorthSouth => '│',
+            Tile::EastWest => '─',
+            Tile::NorthEast => '└',
+            Tile::NorthWest => '┘',
+            Tile::SouthWest => '┐',
+            Tile::SouthEast => '┌',
+            Tile::Empty => '.',
+            Tile::Start => 'S',
+        }
+    }
+
     pub fn is_start(&self) -> bool {
         matches!(self, Tile::Start)
     }
@@ -89,11 +123,27 @@ impl Tile {
 }
 
 impl Vec2d<Tile> {
-    pub fn find_start(&self) -> Cell<Tile> {
+    pub fn find_start(&self) -> Cell<'_, Tile> {
         self.cells().find(|c| c.value().is_start()).unwrap()
     }
 
-    pub fn compute_loop(&self) -> Vec<Cell<Tile>> {
+    /// Draw the pipes, blanking out the ones that are not part of the loop
+    pub fn render_loop(&self) -> Render<'_, Tile> {
+        let boundary: HashSet<_> = self
+            .compute_loop()
+            .iter()
+            .map(|cell| cell.coords())
+            .collect();
+        let outside = self
+            .cells()
+            .map(|cell| cell.coords())
+            .filter(|coords| !boundary.contains(coords));
+        self.render(Tile::glyph)
+            .mark(outside, ' ')
+            .highlight(boundary.iter().copied(), Color::Yellow)
+    }
+
+    pub fn compute_loop(&self) -> Vec<Cell<'_, Tile>> {
         let mut result = Vec::new();
         let mut cur = self.find_start();
         let mut prev = self.find_start();
@@ -116,7 +166,7 @@ impl Vec2d<Tile> {
 }
 
 impl<'a> Cell<'a, Tile> {
-    fn get_next_cells(&'a self) -> Vec<Cell<Tile>> {
+    fn get_next_cells(&'a self) -> Vec<Cell<'a, Tile>> {
         match self.value() {
             Tile::NorthSouth => [self.get_top(), self.get_below()]
                 .into_iter()
@@ -158,57 +208,14 @@ impl<'a> Cell<'a, Tile> {
 }
 #[cfg(test)]
 mod test {
-    use crate::common::Solution;
-    use crate::day10::{Day10, Day10P2};
-
-    const SIMPLE_EXAMPLE_INPUT: &str = r#".....
-.S-7.
-.|.|.
-.L-J.
-....."#;
-
-    const COMPLEX_EXAMPLE_INPUT: &str = r#"..F7.
-.FJ|.
-SJ.L7
-|F--J
-LJ..."#;
-
-    #[test]
-    fn test_simple_example() {
-        assert_eq!(Day10::solve(SIMPLE_EXAMPLE_INPUT.lines()), "4");
-    }
-    #[test]
-    fn test_complex_example() {
-        assert_eq!(Day10::solve(COMPLEX_EXAMPLE_INPUT.lines()), "8");
-    }
-
-    #[test]
-    fn test_part2_example_basic() {
-        let input = r#"...........
-.S-------7.
-.|F-----7|.
-.||.....||.
-.||.....||.
-.|L-7.F-J|.
-.|..|.|..|.
-.L--J.L--J.
-..........."#;
-
-        assert_eq!(Day10P2::solve(input.lines()), "4")
-    }
 
+    use crate::day10::parse_tiles;
     #[test]
-    fn test_part2_example_last() {
-        let input = r#"FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L"#;
-        assert_eq!(Day10P2::solve(input.lines()), "10")
+    fn test_render_loop() {
+        let input = parse_tiles("7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ".lines()).unwrap();
+        assert_eq!(
+            input.render_loop().to_string(),
+            "  ┌┐ \n ┌┘│ \nS┘ └┐\n│┌──┘\n└┘   \n"
+        );
     }
 }
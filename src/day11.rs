@@ -2,57 +2,71 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use crate::common::Solution;
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::{Param, Params};
+use crate::registry::register;
 use crate::vec2d::{Cell, Vec2d};
 
 pub enum Day11 {}
-impl Solution for Day11 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let matrix = Vec2d::from_lines(lines);
-        let matrix = matrix.map(|c| Point::from(*c));
-        let image: Image = matrix.into();
 
-        image
+pub type Day11P2 = Part2<Day11>;
+
+register!(Day11, Day11P2);
+
+const EXPANSION: Param = Param {
+    name: "expansion",
+    part: 2,
+    default: "1000000",
+    description: "Number of rows or columns that each empty row or column becomes",
+};
+
+impl Puzzle for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Cosmic Expansion";
+    const PARAMS: &'static [Param] = &[EXPANSION];
+
+    type Input = Image;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Ok(parse_grid(lines, "parse image", Point::try_from)?.into())
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
             .get_galaxies()
             .combinations(2)
             .map(|val| {
                 let [a, b] = val.as_slice() else { panic!() };
 
-                image.dist(a.coords().into(), b.coords().into(), 2)
+                input.dist(a.coords().into(), b.coords().into(), 2)
             })
             .sum::<usize>()
-            .to_string()
+            .into())
     }
-}
-
-pub enum Day11P2 {}
-impl Solution for Day11P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let matrix = Vec2d::from_lines(lines);
-        let matrix = matrix.map(|c| Point::from(*c));
-        let image: Image = matrix.into();
 
-        image
+    fn part2(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        let expansion = params.get(&EXPANSION)?;
+        Ok(input
             .get_galaxies()
             .combinations(2)
             .map(|val| {
                 let [a, b] = val.as_slice() else { panic!() };
 
-                image.dist(a.coords().into(), b.coords().into(), 1_000_000)
+                input.dist(a.coords().into(), b.coords().into(), expansion)
             })
             .sum::<usize>()
-            .to_string()
+            .into())
     }
 }
 
-struct Image {
+pub struct Image {
     empty_rows: HashSet<usize>,
     empty_cols: HashSet<usize>,
     points: Vec2d<Point>,
 }
 
 impl Image {
-    pub fn get_galaxies(&self) -> impl Iterator<Item = Cell<Point>> {
+    pub fn get_galaxies(&self) -> impl Iterator<Item = Cell<'_, Point>> {
         self.points.cells().filter(|cell| cell.value().is_galaxy())
     }
 
@@ -97,7 +111,7 @@ impl From<Vec2d<Point>> for Image {
         let mut empty_rows = HashSet::new();
         let mut empty_cols = HashSet::new();
 
-        let col_count = value.get_row(0).unwrap().len();
+        let col_count = value.num_cols();
         (0..col_count)
             .filter(|col| {
                 value
@@ -109,8 +123,7 @@ impl From<Vec2d<Point>> for Image {
             });
 
         value
-            .inner
-            .iter()
+            .rows()
             .enumerate()
             .filter(|(_, row)| row.iter().all(|cell| !cell.is_galaxy()))
             .for_each(|(index, _)| {
@@ -125,7 +138,7 @@ impl From<Vec2d<Point>> for Image {
     }
 }
 
-enum Point {
+pub enum Point {
     Space,
     Galaxy,
 }
@@ -136,32 +149,14 @@ impl Point {
     }
 }
 
-impl From<char> for Point {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Point {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '#' => Point::Galaxy,
-            _ => Point::Space,
+            '#' => Ok(Point::Galaxy),
+            '.' => Ok(Point::Space),
+            _ => Err(anyhow::Error::msg("Invalid char received").context(value)),
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day11::Day11;
-
-    const INPUT: &str = r#"...#......
-.......#..
-#.........
-..........
-......#...
-.#........
-.........#
-..........
-.......#..
-#...#....."#;
-    #[test]
-    fn test_example() {
-        assert_eq!(Day11::solve(INPUT.lines()), "374")
-    }
-}
@@ -1,48 +1,69 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::{Param, Params};
+use crate::registry::register;
 
 pub enum Day12 {}
 
-impl Solution for Day12 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|line| line.as_ref().parse::<Record>().unwrap().get_arrangements())
-            .sum::<usize>()
-            .to_string()
+pub type Day12P2 = Part2<Day12>;
+
+register!(Day12, Day12P2);
+
+const FOLDS: Param = Param {
+    name: "folds",
+    part: 2,
+    default: "5",
+    description: "Number of copies that each record unfolds into",
+};
+
+impl Puzzle for Day12 {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Hot Springs";
+    const PARAMS: &'static [Param] = &[FOLDS];
+
+    type Input = Vec<Record>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_lines(lines, "parse record", str::parse)
     }
-}
 
-pub enum Day12P2 {}
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
+            .map(|record| record.get_arrangements())
+            .sum::<usize>()
+            .into())
+    }
 
-impl Solution for Day12P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|line| {
-                let record = line.as_ref().parse::<Record>().unwrap();
+    fn part2(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        let folds: usize = params.get(&FOLDS)?;
+        Ok(input
+            .iter()
+            .map(|record| {
                 let record = Record {
-                    springs: (0..5)
+                    springs: (0..folds)
                         .flat_map(|i| {
                             let mut springs = record.springs.clone();
-                            if i != 4 {
+                            if i + 1 != folds {
                                 springs.push(Condition::Unknown);
                             }
                             springs
                         })
                         .collect(),
-                    damaged_records: (0..5)
+                    damaged_records: (0..folds)
                         .flat_map(|_| record.damaged_records.clone())
                         .collect(),
                 };
                 record.get_arrangements()
             })
             .sum::<usize>()
-            .to_string()
+            .into())
     }
 }
 
-struct Record {
+pub struct Record {
     springs: Vec<Condition>,
     damaged_records: Vec<usize>,
 }
@@ -139,8 +160,14 @@ impl FromStr for Record {
             .ok_or(anyhow::Error::msg("Received bad input").context(s.to_string()))?;
 
         Ok(Record {
-            springs: springs.chars().map(|c| c.into()).collect(),
-            damaged_records: reports.split(',').map(|val| val.parse().unwrap()).collect(),
+            springs: springs
+                .chars()
+                .map(Condition::try_from)
+                .collect::<Result<_, _>>()?,
+            damaged_records: reports
+                .split(',')
+                .map(|val| val.parse())
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -162,35 +189,15 @@ impl Condition {
     }
 }
 
-impl From<char> for Condition {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Condition {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Condition::Operational,
-            '#' => Condition::Damaged,
-            '?' => Condition::Unknown,
-            _ => panic!("Unexpected value received: {}", value),
+            '.' => Ok(Condition::Operational),
+            '#' => Ok(Condition::Damaged),
+            '?' => Ok(Condition::Unknown),
+            _ => Err(anyhow::Error::msg("Unexpected value received").context(value)),
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day12::{Day12, Day12P2};
-
-    const EXAMPLE_INPUT: &str = r#"???.### 1,1,3
-.??..??...?##. 1,1,3
-?#?#?#?#?#?#?#? 1,3,1,6
-????.#...#... 4,1,1
-????.######..#####. 1,6,5
-?###???????? 3,2,1"#;
-    #[test]
-    fn test_example() {
-        assert_eq!(Day12::solve(EXAMPLE_INPUT.lines()), "21")
-    }
-
-    #[test]
-    fn test_example_part2() {
-        assert_eq!(Day12P2::solve(EXAMPLE_INPUT.lines()), "525152")
-    }
-}
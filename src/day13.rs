@@ -1,192 +1,101 @@
 use std::iter::zip;
-use std::str::FromStr;
 
-use itertools::Itertools;
-
-use crate::common::Solution;
-use crate::vec2d::Vec2d;
+use crate::common::{parse_grid, split_blocks, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
+use crate::vec2d::{Vec2d, View};
 
 pub enum Day13 {}
 
-impl Solution for Day13 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let combined = lines.map(|s| s.as_ref().to_string()).join("\n");
+pub type Day13P2 = Part2<Day13>;
 
-        combined
-            .split("\n\n")
-            .map(|s| s.parse::<Pattern>().unwrap().get_reflection_summary())
-            .sum::<usize>()
-            .to_string()
-    }
-}
+register!(Day13, Day13P2);
 
-pub enum Day13P2 {}
+impl Puzzle for Day13 {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Point of Incidence";
 
-impl Solution for Day13P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let combined = lines.map(|s| s.as_ref().to_string()).join("\n");
+    /// Each pattern along with the number of lines before it
+    type Input = Vec<(usize, Pattern)>;
 
-        combined
-            .split("\n\n")
-            .map(|s| s.parse::<Pattern>().unwrap().get_reflection_summary_p2())
-            .sum::<usize>()
-            .to_string()
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        split_blocks(lines)
+            .into_iter()
+            .map(|(offset, block)| {
+                let pattern =
+                    Pattern::from_lines(block.iter()).map_err(|err| err.offset_line(offset))?;
+                Ok((offset, pattern))
+            })
+            .collect()
     }
-}
-
-struct Pattern {
-    tiles: Vec2d<Tile>,
-}
 
-impl Pattern {
-    pub fn get_reflection_summary(&self) -> usize {
-        if let Some(row) = self.get_reflecting_row() {
-            row * 100
-        } else {
-            self.get_reflecting_col().unwrap()
-        }
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        sum_summaries(input, Pattern::get_reflection_summary)
     }
 
-    pub fn get_reflection_summary_p2(&self) -> usize {
-        if let Some(row) = self.get_reflecting_row_p2() {
-            row * 100
-        } else {
-            self.get_reflecting_col_p2().unwrap()
-        }
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        sum_summaries(input, Pattern::get_reflection_summary_p2)
     }
+}
 
-    fn get_reflecting_row_p2(&self) -> Option<usize> {
-        let num_rows = self.tiles.num_rows();
-        for row in 0..(num_rows - 1) {
-            let mut used_smudge = false;
-            for margin in 1..num_rows {
-                if let Some(it) = self.reflect_below_row(row, margin) {
-                    let diff_count = it.filter(|(a, b)| a.ne(b)).count();
-                    if diff_count == 1 && used_smudge {
-                        break;
-                    } else if diff_count == 1 {
-                        used_smudge = true;
-                    } else if diff_count > 0 {
-                        break;
-                    }
-                } else if used_smudge {
-                    return Some(row + 1);
-                } else {
-                    break;
-                }
-            }
-        }
-        None
+fn sum_summaries<F>(patterns: &[(usize, Pattern)], summarize: F) -> Result<Answer, SolveError>
+where
+    F: Fn(&Pattern) -> Option<usize>,
+{
+    let mut sum = 0;
+    for (offset, pattern) in patterns {
+        sum += summarize(pattern).ok_or_else(|| {
+            SolveError::msg("find reflection", "pattern has no line of reflection")
+                .on_line(offset + 1)
+        })?;
     }
+    Ok(sum.into())
+}
 
-    fn get_reflecting_col_p2(&self) -> Option<usize> {
-        let num_cols = self.get_num_cols();
-        for col in 0..(num_cols - 1) {
-            let mut used_smudge = false;
-            for margin in 1..num_cols {
-                if let Some(it) = self.reflect_right_of_col(col, margin) {
-                    let diff_count = it.filter(|(a, b)| a.ne(b)).count();
-                    if diff_count == 1 && used_smudge {
-                        break;
-                    } else if diff_count == 1 {
-                        used_smudge = true;
-                    } else if diff_count > 0 {
-                        break;
-                    }
-                } else if used_smudge {
-                    return Some(col + 1);
-                } else {
-                    break;
-                }
-            }
-        }
-        None
-    }
+pub struct Pattern {
+    tiles: Vec2d<Tile>,
+}
 
-    fn get_reflecting_col(&self) -> Option<usize> {
-        let num_cols = self.get_num_cols();
-        for col in 0..(num_cols - 1) {
-            for margin in 1..num_cols {
-                if let Some(mut it) = self.reflect_right_of_col(col, margin) {
-                    if !it.all(|(a, b)| a.eq(&b)) {
-                        break;
-                    }
-                } else {
-                    return Some(col + 1);
-                }
-            }
-        }
-        None
+impl Pattern {
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Pattern, SolveError> {
+        Ok(Pattern {
+            tiles: parse_grid(lines, "parse pattern", Tile::try_from)?,
+        })
     }
 
-    fn get_reflecting_row(&self) -> Option<usize> {
-        let num_rows = self.tiles.num_rows();
-        for row in 0..(num_rows - 1) {
-            for margin in 1..num_rows {
-                if let Some(mut it) = self.reflect_below_row(row, margin) {
-                    if !it.all(|(a, b)| a.eq(&b)) {
-                        break;
-                    }
-                } else {
-                    return Some(row + 1);
-                }
-            }
-        }
-        None
+    pub fn get_reflection_summary(&self) -> Option<usize> {
+        self.get_summary_with_smudges(0)
     }
 
-    fn reflect_right_of_col(
-        &self,
-        col: usize,
-        margin: usize,
-    ) -> Option<impl Iterator<Item = (Tile, Tile)> + '_> {
-        let Some(left_col) = (col + 1).checked_sub(margin) else {
-            return None;
-        };
-        let right_col = col + margin;
-        if right_col >= self.get_num_cols() {
-            return None;
-        }
-
-        Some(zip(
-            self.tiles.get_col(left_col).copied(),
-            self.tiles.get_col(right_col).copied(),
-        ))
+    pub fn get_reflection_summary_p2(&self) -> Option<usize> {
+        self.get_summary_with_smudges(1)
     }
 
-    fn reflect_below_row(
-        &self,
-        row: usize,
-        margin: usize,
-    ) -> Option<impl Iterator<Item = (Tile, Tile)> + '_> {
-        let Some(bottom_row) = (row + 1).checked_sub(margin) else {
-            return None;
-        };
-        let top_row = row + margin;
-        if top_row >= self.tiles.inner.len() {
-            return None;
+    fn get_summary_with_smudges(&self, smudges: usize) -> Option<usize> {
+        if let Some(row) = get_reflecting_row(self.tiles.view(), smudges) {
+            Some(row * 100)
+        } else {
+            // The columns of the pattern are the rows of its transpose
+            get_reflecting_row(self.tiles.transpose(), smudges)
         }
-
-        Some(zip(
-            self.tiles.get_row(bottom_row).unwrap().iter().copied(),
-            self.tiles.get_row(top_row).unwrap().iter().copied(),
-        ))
-    }
-
-    fn get_num_cols(&self) -> usize {
-        self.tiles.first_num_cols()
     }
 }
 
-impl FromStr for Pattern {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let matrix = Vec2d::from_lines(s.lines());
-        Ok(Pattern {
-            tiles: matrix.map(|c| Tile::from(*c)),
-        })
-    }
+/// Find the number of rows above a horizontal line of reflection, where the reflected rows
+/// differ in exactly `smudges` tiles
+fn get_reflecting_row(tiles: View<'_, Tile>, smudges: usize) -> Option<usize> {
+    let num_rows = tiles.num_rows();
+    (1..num_rows).find(|&row| {
+        let margin = row.min(num_rows - row);
+        let differences: usize = (0..margin)
+            .map(|offset| {
+                zip(tiles.get_row(row - 1 - offset), tiles.get_row(row + offset))
+                    .filter(|(a, b)| a != b)
+                    .count()
+            })
+            .sum();
+        differences == smudges
+    })
 }
 
 #[derive(Eq, PartialEq, Hash, Copy, Clone)]
@@ -195,43 +104,14 @@ enum Tile {
     Rock,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Tile::Ash,
-            '#' => Tile::Rock,
-            _ => panic!("Received invalid value: {}", value),
+            '.' => Ok(Tile::Ash),
+            '#' => Ok(Tile::Rock),
+            _ => Err(anyhow::Error::msg("Received invalid value").context(value)),
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day13::{Day13, Day13P2};
-
-    const EXAMPLE_INPUT: &str = r#"#.##..##.
-..#.##.#.
-##......#
-##......#
-..#.##.#.
-..##..##.
-#.#.##.#.
-
-#...##..#
-#....#..#
-..##..###
-#####.##.
-#####.##.
-..##..###
-#....#..#"#;
-    #[test]
-    fn test_example() {
-        assert_eq!(Day13::solve(EXAMPLE_INPUT.lines()), "405")
-    }
-
-    #[test]
-    fn test_example_part2() {
-        assert_eq!(Day13P2::solve(EXAMPLE_INPUT.lines()), "400")
-    }
-}
@@ -2,101 +2,94 @@ use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter, Write};
 
-use crate::common::Solution;
-use crate::vec2d::{RowCol, Vec2d};
+use crate::animation::Observer;
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
+use crate::vec2d::{Direction, RowCol, Vec2d};
 
 pub enum Day14 {}
 
-impl Solution for Day14 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut grid = Grid::from_lines(lines);
-        grid.tilt_north();
-        grid.get_weight().to_string()
+pub type Day14P2 = Part2<Day14>;
+
+register!(Day14, Day14P2);
+
+impl Puzzle for Day14 {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Parabolic Reflector Dish";
+
+    type Input = Grid;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Grid::from_lines(lines)
     }
-}
 
-pub enum Day14P2 {}
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let mut grid = input.clone();
+        grid.tilt(Direction::Up);
+        Ok(grid.get_weight().into())
+    }
 
-impl Solution for Day14P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut grid = Grid::from_lines(lines);
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let mut grid = input.clone();
         grid.cycle_iterations(1000000000);
-        grid.get_weight().to_string()
+        Ok(grid.get_weight().into())
     }
 }
 
 #[derive(Clone)]
-struct Grid {
+pub struct Grid {
     tiles: Vec2d<Tile>,
 }
 
 impl Grid {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Grid {
-        let matrix = Vec2d::from_lines(lines);
-        Grid {
-            tiles: matrix.map(|&c| c.into()),
-        }
-    }
-
-    pub fn tilt_north(&mut self) {
-        for row in 0..self.tiles.num_rows() {
-            for col in 0..self.tiles.first_num_cols() {
-                let tile = self.tiles.get(row, col).unwrap();
-                if tile.is_rounded() {
-                    let new_spot = self.find_last_empty_north((row, col).into());
-                    self.move_tile(&(row, col).into(), &new_spot);
-                }
-            }
-        }
-    }
-
-    pub fn tilt_south(&mut self) {
-        for row in (0..self.tiles.num_rows()).rev() {
-            for col in 0..self.tiles.first_num_cols() {
-                let tile = self.tiles.get(row, col).unwrap();
-                if tile.is_rounded() {
-                    let new_spot = self.find_last_empty_south((row, col).into());
-                    self.move_tile(&(row, col).into(), &new_spot);
-                }
-            }
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Grid, SolveError> {
+        Ok(Grid {
+            tiles: parse_grid(lines, "parse rock", Tile::try_from)?,
+        })
+    }
+
+    /// Roll every round rock as far as it will go in `direction`
+    pub fn tilt(&mut self, direction: Direction) {
+        // Roll the rocks nearest the edge first, so that each rock stops against the ones that
+        // have already rolled
+        let mut rocks: Vec<_> = self.get_rounded_locations().collect();
+        if matches!(direction, Direction::Down | Direction::Right) {
+            rocks.reverse();
         }
-    }
-
-    pub fn tilt_west(&mut self) {
-        for row in 0..self.tiles.num_rows() {
-            for col in 0..self.tiles.first_num_cols() {
-                let tile = self.tiles.get(row, col).unwrap();
-                if tile.is_rounded() {
-                    let new_spot = self.find_last_empty_west((row, col).into());
-                    self.move_tile(&(row, col).into(), &new_spot);
-                }
-            }
-        }
-    }
-
-    pub fn tilt_east(&mut self) {
-        for row in 0..self.tiles.num_rows() {
-            for col in (0..self.tiles.first_num_cols()).rev() {
-                let tile = self.tiles.get(row, col).unwrap();
-                if tile.is_rounded() {
-                    let new_spot = self.find_last_empty_east((row, col).into());
-                    self.move_tile(&(row, col).into(), &new_spot);
-                }
-            }
+        for rock in rocks {
+            let end = self
+                .tiles
+                .get_cell(rock.row, rock.col)
+                .unwrap()
+                .find_last_satisfying_in(direction, Tile::is_empty)
+                .coords();
+            self.move_tile(&rock, &end);
         }
     }
 
     pub fn cycle(&mut self) {
-        self.tilt_north();
-        self.tilt_west();
-        self.tilt_south();
-        self.tilt_east();
+        self.tilt(Direction::Up);
+        self.tilt(Direction::Left);
+        self.tilt(Direction::Down);
+        self.tilt(Direction::Right);
     }
 
     pub fn cycle_iterations(&mut self, iterations: usize) {
+        self.cycle_iterations_observed(iterations, &mut ());
+    }
+
+    /// Run `iterations` spin cycles, skipping ahead once the rocks repeat a position, and show
+    /// `observer` the grid after every cycle that is actually run
+    pub fn cycle_iterations_observed(
+        &mut self,
+        iterations: usize,
+        observer: &mut impl Observer<Grid>,
+    ) {
         let mut last_index: HashMap<Vec<RowCol>, usize> = HashMap::new();
         for i in 0..iterations {
             self.cycle();
+            observer.observe(self);
             let positions: Vec<_> = self.get_rounded_locations().collect();
             let entry = last_index.entry(positions);
             match entry {
@@ -108,6 +101,7 @@ impl Grid {
                     // slightly suboptimal but probably easier to just run the cycles manually
                     for _ in 0..end_cycle_index {
                         self.cycle();
+                        observer.observe(self);
                     }
                     return;
                 }
@@ -119,11 +113,8 @@ impl Grid {
     }
 
     fn move_tile(&mut self, start: &RowCol, end: &RowCol) {
-        let tile = *self.tiles.get(start.row, start.col).unwrap();
-        *self.tiles.get_mut(start.row, start.col).unwrap() = Tile::Empty;
-        let dest = self.tiles.get_mut(end.row, end.col).unwrap();
-        debug_assert!(dest.is_empty());
-        *dest = tile;
+        debug_assert!(self.tiles.get_row_col(end).unwrap().is_empty() || start == end);
+        self.tiles.swap(start, end);
     }
 
     pub fn get_weight(&self) -> usize {
@@ -134,41 +125,9 @@ impl Grid {
             .sum()
     }
 
-    fn find_last_empty_north(&self, row_col: RowCol) -> RowCol {
-        let col = row_col.col;
-        let row = (0..row_col.row)
-            .rev()
-            .find(|row| !self.tiles.get(*row, col).unwrap().is_empty())
-            .map(|row| row + 1)
-            .unwrap_or(0);
-        RowCol { row, col }
-    }
-    fn find_last_empty_south(&self, row_col: RowCol) -> RowCol {
-        let col = row_col.col;
-        let row = ((row_col.row + 1)..self.tiles.num_rows())
-            .find(|row| !self.tiles.get(*row, col).unwrap().is_empty())
-            .map(|row| row - 1)
-            .unwrap_or(self.tiles.num_rows() - 1);
-        RowCol { row, col }
-    }
-
-    fn find_last_empty_west(&self, row_col: RowCol) -> RowCol {
-        let row = row_col.row;
-        let col = (0..row_col.col)
-            .rev()
-            .find(|&col| !self.tiles.get(row, col).unwrap().is_empty())
-            .map(|col| col + 1)
-            .unwrap_or(0);
-        RowCol { row, col }
-    }
-
-    fn find_last_empty_east(&self, row_col: RowCol) -> RowCol {
-        let row = row_col.row;
-        let col = ((row_col.col + 1)..self.tiles.first_num_cols())
-            .find(|&col| !self.tiles.get(row, col).unwrap().is_empty())
-            .map(|col| col - 1)
-            .unwrap_or(self.tiles.first_num_cols() - 1);
-        RowCol { row, col }
+    /// The grid as it is drawn in the puzzle description, for recording animations
+    pub fn frame(&self) -> Vec2d<char> {
+        self.tiles.map(|&tile| tile.into())
     }
 
     pub fn get_rounded_locations(&self) -> impl Iterator<Item = RowCol> + '_ {
@@ -186,13 +145,15 @@ enum Tile {
     Square,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Tile::Empty,
-            '#' => Tile::Square,
-            'O' => Tile::Round,
-            _ => panic!("Invalid value received: {}", value),
+            '.' => Ok(Tile::Empty),
+            '#' => Ok(Tile::Square),
+            'O' => Ok(Tile::Round),
+            _ => Err(anyhow::Error::msg("Invalid value received").context(value)),
         }
     }
 }
@@ -207,14 +168,19 @@ impl Tile {
     }
 }
 
+impl From<Tile> for char {
+    fn from(tile: Tile) -> char {
+        match tile {
+            Tile::Empty => '.',
+            Tile::Round => 'O',
+            Tile::Square => '#',
+        }
+    }
+}
+
 impl Display for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Tile::Empty => f.write_char('.')?,
-            Tile::Round => f.write_char('O')?,
-            Tile::Square => f.write_char('#')?,
-        };
-        Ok(())
+        f.write_char((*self).into())
     }
 }
 
@@ -226,27 +192,31 @@ impl Debug for Tile {
 
 #[cfg(test)]
 mod test {
-    use crate::common::Solution;
-    use crate::day14::{Day14, Day14P2};
-
-    const EXAMPLE_INPUT: &str = r#"O....#....
-O.OO#....#
-.....##...
-OO.#O....O
-.O.....O#.
-O.#..O.#.#
-..O..#O..O
-.......O..
-#....###..
-#OO..#...."#;
+    use crate::animation::Animation;
+    use crate::example::example_input;
 
-    #[test]
-    fn test_example() {
-        assert_eq!(Day14::solve(EXAMPLE_INPUT.lines()), "136");
-    }
+    use crate::day14::Grid;
 
     #[test]
-    fn test_example_p2() {
-        assert_eq!(Day14P2::solve(EXAMPLE_INPUT.lines()), "64");
+    fn test_record_cycles() {
+        let mut grid = Grid::from_lines(example_input!("day14/example.txt").iter()).unwrap();
+        let mut animation = Animation::new();
+        grid.cycle_iterations_observed(3, &mut animation.recorder(Grid::frame));
+        assert_eq!(animation.len(), 3);
+        let after_one_cycle = animation.frames()[0].render(|&c| c).to_string();
+        assert_eq!(
+            after_one_cycle,
+            ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+"
+        );
     }
 }
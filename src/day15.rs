@@ -2,37 +2,51 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day15 {}
-impl Solution for Day15 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
+
+pub type Day15P2 = Part2<Day15>;
+
+register!(Day15, Day15P2);
+
+impl Puzzle for Day15 {
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Lens Library";
+
+    /// The lines of the initialization sequence, which are read differently by each part
+    type Input = Vec<String>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Ok(lines.map(|line| line.as_ref().to_string()).collect())
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
             .map(|line| {
-                line.as_ref()
-                    .split(',')
+                line.split(',')
                     .map(|s| s.chars().fold(0, update_hash))
                     .sum::<usize>()
             })
             .sum::<usize>()
-            .to_string()
+            .into())
     }
-}
 
-pub enum Day15P2 {}
-impl Solution for Day15P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
         let mut map = LensHashMap::new();
-        lines
-            .flat_map(|line| {
-                line.as_ref()
-                    .split(',')
-                    .map(|s| s.parse::<LensOp>().unwrap())
-                    .collect_vec()
-            })
-            .for_each(|val| map.perform_op(val));
-
-        map.get_summary().to_string()
+        parse_lines(input.iter(), "parse lens operation", |line| {
+            line.split(',')
+                .map(|s| s.parse::<LensOp>())
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?
+        .into_iter()
+        .flatten()
+        .for_each(|val| map.perform_op(val));
+
+        Ok(map.get_summary().into())
     }
 }
 
@@ -109,41 +123,16 @@ impl FromStr for LensOp {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (index, op) = s
-            .chars()
-            .enumerate()
+            .char_indices()
             .find(|(_, c)| *c == '=' || *c == '-')
-            .unwrap();
+            .ok_or_else(|| anyhow::Error::msg("Missing lens operation").context(s.to_string()))?;
+        let (label, rest) = s.split_at(index);
         match op {
-            '=' => {
-                let (label, focal_length) = s.split_once('=').unwrap();
-                Ok(LensOp::Put(Lens {
-                    label: label.to_string(),
-                    focal_length: focal_length.parse()?,
-                }))
-            }
-            '-' => {
-                let (label, _) = s.split_at(index);
-                Ok(LensOp::Remove(label.to_string()))
-            }
-            _ => panic!(),
+            '=' => Ok(LensOp::Put(Lens {
+                label: label.to_string(),
+                focal_length: rest[1..].parse()?,
+            })),
+            _ => Ok(LensOp::Remove(label.to_string())),
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day15::{Day15, Day15P2};
-
-    const EXAMPLE_INPUT: &str = r"rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(Day15::solve(EXAMPLE_INPUT.lines()), "1320")
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day15P2::solve(EXAMPLE_INPUT.lines()), "145")
-    }
-}
@@ -1,48 +1,53 @@
 use std::collections::HashSet;
 
-use crate::common::Solution;
+use crate::animation::Observer;
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 use crate::vec2d::{Direction, RowCol, Vec2d};
 
 pub enum Day16 {}
 
-impl Solution for Day16 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut grid: Grid = Vec2d::from_lines(lines)
-            .map(|c| Tile::from(*c))
-            .map(|tile| (*tile).into())
-            .into();
+pub type Day16P2 = Part2<Day16>;
 
-        grid.travel_and_mark(
-            grid.cells.top_left_cell().unwrap().coords(),
-            Direction::Right,
-        );
+register!(Day16, Day16P2);
+
+impl Puzzle for Day16 {
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "The Floor Will Be Lava";
 
-        grid.count_energized().to_string()
+    type Input = Vec2d<Tile>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_grid(lines, "parse tile", Tile::try_from)
     }
-}
 
-pub enum Day16P2 {}
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let mut grid = Grid::new(input);
+
+        grid.travel_and_mark(RowCol { row: 0, col: 0 }, Direction::Right, &mut ());
+
+        Ok(grid.count_energized().into())
+    }
 
-impl Solution for Day16P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let tiles = Vec2d::from_lines(lines).map(|c| Tile::from(*c));
-        get_border_and_directions(&tiles)
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(get_border_and_directions(input)
             .map(|(start, direction)| {
-                let mut grid: Grid = tiles.map(|tile| (*tile).into()).into();
+                let mut grid = Grid::new(input);
 
-                grid.travel_and_mark(start, direction);
+                grid.travel_and_mark(start, direction, &mut ());
 
                 grid.count_energized()
             })
             .max()
-            .unwrap()
-            .to_string()
+            .unwrap_or(0)
+            .into())
     }
 }
 
 fn get_border_and_directions<T>(grid: &Vec2d<T>) -> impl Iterator<Item = (RowCol, Direction)> {
     let num_rows = grid.num_rows();
-    let num_cols = grid.first_num_cols();
+    let num_cols = grid.num_cols();
     (0..num_rows)
         .flat_map(move |row| {
             [
@@ -70,13 +75,25 @@ fn get_border_and_directions<T>(grid: &Vec2d<T>) -> impl Iterator<Item = (RowCol
         }))
 }
 
-struct Grid {
+/// The contraption with the beams that have passed through each tile
+pub struct Grid {
     cells: Vec2d<GridCell>,
 }
 
 impl Grid {
-    pub fn travel_and_mark(&mut self, start: RowCol, direction: Direction) {
-        let Some(cur_cell) = self.cells.get_mut(start.row, start.col) else {
+    pub fn new(tiles: &Vec2d<Tile>) -> Grid {
+        tiles.map(|tile| (*tile).into()).into()
+    }
+
+    /// Follow the beam entering `start` in `direction`, marking the tiles it passes through as
+    /// energized and showing `observer` the grid after every tile
+    pub fn travel_and_mark(
+        &mut self,
+        start: RowCol,
+        direction: Direction,
+        observer: &mut impl Observer<Grid>,
+    ) {
+        let Some(mut cur_cell) = self.cells.get_cell_mut(start.row, start.col) else {
             return;
         };
         if cur_cell.energized && cur_cell.traveled_directions.contains(&direction) {
@@ -87,56 +104,35 @@ impl Grid {
         }
 
         let tile = cur_cell.tile;
-
-        match (tile, direction) {
-            (Tile::Empty, _)
-            | (Tile::VerticalSplit, Direction::Up | Direction::Down)
-            | (Tile::HorizontalSplit, Direction::Left | Direction::Right) => {
-                if let Some(next) = direction.next(start) {
-                    self.travel_and_mark(next, direction)
-                }
-            }
-            (Tile::ForwardMirror, Direction::Right) | (Tile::BackwardMirror, Direction::Left) => {
-                if let Some(next) = Direction::Up.next(start) {
-                    self.travel_and_mark(next, Direction::Up)
-                }
-            }
-            (Tile::BackwardMirror, Direction::Right) | (Tile::ForwardMirror, Direction::Left) => {
-                if let Some(next) = Direction::Down.next(start) {
-                    self.travel_and_mark(next, Direction::Down)
-                }
-            }
-            (Tile::ForwardMirror, Direction::Down) | (Tile::BackwardMirror, Direction::Up) => {
-                if let Some(next) = Direction::Left.next(start) {
-                    self.travel_and_mark(next, Direction::Left)
-                }
+        observer.observe(self);
+
+        let next_directions = match tile {
+            Tile::Empty => vec![direction],
+            Tile::ForwardMirror => vec![direction.reflect_forward()],
+            Tile::BackwardMirror => vec![direction.reflect_backward()],
+            // splitters are passed straight through along their length
+            Tile::VerticalSplit if direction.is_vertical() => vec![direction],
+            Tile::HorizontalSplit if !direction.is_vertical() => vec![direction],
+            Tile::VerticalSplit | Tile::HorizontalSplit => {
+                vec![direction.turn_left(), direction.turn_right()]
             }
-            (Tile::ForwardMirror, Direction::Up) | (Tile::BackwardMirror, Direction::Down) => {
-                if let Some(next) = Direction::Right.next(start) {
-                    self.travel_and_mark(next, Direction::Right)
-                }
-            }
-            (Tile::VerticalSplit, Direction::Left | Direction::Right) => {
-                if let Some(next) = Direction::Up.next(start) {
-                    self.travel_and_mark(next, Direction::Up)
-                }
-                if let Some(next) = Direction::Down.next(start) {
-                    self.travel_and_mark(next, Direction::Down)
-                }
-            }
-            (Tile::HorizontalSplit, Direction::Up | Direction::Down) => {
-                if let Some(next) = Direction::Left.next(start) {
-                    self.travel_and_mark(next, Direction::Left);
-                }
-                if let Some(next) = Direction::Right.next(start) {
-                    self.travel_and_mark(next, Direction::Right)
-                }
+        };
+        for direction in next_directions {
+            if let Some(next) = direction.next(start) {
+                self.travel_and_mark(next, direction, observer);
             }
         }
     }
+
     pub fn count_energized(&self) -> usize {
         self.cells.cells().filter(|cell| cell.energized).count()
     }
+
+    /// The energized tiles drawn as `#` as in the puzzle description, for recording animations
+    pub fn frame(&self) -> Vec2d<char> {
+        self.cells
+            .map(|cell| if cell.energized { '#' } else { '.' })
+    }
 }
 
 impl From<Vec2d<GridCell>> for Grid {
@@ -162,7 +158,7 @@ impl From<Tile> for GridCell {
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
-enum Tile {
+pub enum Tile {
     // '.'
     Empty,
     // '|'
@@ -175,43 +171,61 @@ enum Tile {
     BackwardMirror,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Tile::Empty,
-            '|' => Tile::VerticalSplit,
-            '-' => Tile::HorizontalSplit,
-            '/' => Tile::ForwardMirror,
-            '\\' => Tile::BackwardMirror,
-            _ => panic!("Invalid char received: {}", value),
+            '.' => Ok(Tile::Empty),
+            '|' => Ok(Tile::VerticalSplit),
+            '-' => Ok(Tile::HorizontalSplit),
+            '/' => Ok(Tile::ForwardMirror),
+            '\\' => Ok(Tile::BackwardMirror),
+            _ => Err(anyhow::Error::msg("Invalid char received").context(value)),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::common::Solution;
-    use crate::day16::{Day16, Day16P2};
-
-    const EXAMPLE_INPUT: &str = r#".|...\....
-|.-.\.....
-.....|-...
-........|.
-..........
-.........\
-..../.\\..
-.-.-/..|..
-.|....-|.\
-..//.|...."#;
-
-    #[test]
-    fn test_example() {
-        assert_eq!(Day16::solve(EXAMPLE_INPUT.lines()), "46")
-    }
+    use crate::animation::Animation;
+    use crate::common::Puzzle;
+    use crate::day16::{Day16, Grid};
+    use crate::example::example_input;
+    use crate::vec2d::{Direction, RowCol};
 
     #[test]
-
-    fn test_example_p2() {
-        assert_eq!(Day16P2::solve(EXAMPLE_INPUT.lines()), "51")
+    fn test_record_beam() {
+        let tiles = <Day16 as Puzzle>::parse(example_input!("day16/example.txt").iter()).unwrap();
+        let mut grid = Grid::new(&tiles);
+        let mut animation = Animation::new();
+        grid.travel_and_mark(
+            RowCol { row: 0, col: 0 },
+            Direction::Right,
+            &mut animation.recorder(Grid::frame),
+        );
+        let first = animation.frames().first().unwrap();
+        assert_eq!(first.cells().filter(|cell| *cell.value() == '#').count(), 1);
+        let last = animation
+            .frames()
+            .last()
+            .unwrap()
+            .render(|&c| c)
+            .to_string();
+        assert_eq!(
+            last,
+            "######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..
+"
+        );
+        assert_eq!(grid.count_energized(), 46);
     }
 }
@@ -1,47 +1,62 @@
-use std::ops::Sub;
 use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::common::Solution;
-use crate::vec2d::Direction;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
+use crate::vec2d::{Direction, Pos, SparseGrid};
 
 pub enum Day18 {}
 
-impl Solution for Day18 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let plan = Plan::from_lines(lines);
-        plan.get_area().to_string()
+pub type Day18P2 = Part2<Day18>;
+
+register!(Day18, Day18P2);
+
+impl Puzzle for Day18 {
+    const DAY: u8 = 18;
+    const TITLE: &'static str = "Lavaduct Lagoon";
+
+    type Input = Plan;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Plan::from_lines(lines)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.get_area().into())
     }
-}
 
-pub enum Day18P2 {}
-impl Solution for Day18P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let plan = Plan::from_lines(lines).part2();
-        plan.get_area().to_string()
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let plan = input.part2()?;
+        Ok(plan.get_area().into())
     }
 }
 
-struct Plan {
+pub struct Plan {
     instructions: Vec<Instruction>,
 }
 
 impl Plan {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Self {
-        Plan {
-            instructions: lines.map(|s| s.as_ref().parse().unwrap()).collect(),
-        }
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self, SolveError> {
+        Ok(Plan {
+            instructions: parse_lines(lines, "parse instruction", str::parse)?,
+        })
     }
 
-    pub fn part2(self) -> Self {
-        Plan {
+    pub fn part2(&self) -> Result<Self, SolveError> {
+        Ok(Plan {
             instructions: self
                 .instructions
-                .into_iter()
-                .map(|instruction| instruction.part2())
-                .collect(),
-        }
+                .iter()
+                .enumerate()
+                .map(|(index, instruction)| {
+                    instruction.part2().map_err(|err| {
+                        SolveError::new("decode instruction color", err).on_line(index + 1)
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        })
     }
 
     pub fn get_area(&self) -> usize {
@@ -50,7 +65,7 @@ impl Plan {
             .get_path()
             .iter()
             .map(|(_, pos)| pos)
-            .chain([SignedRowCol { row: 0, col: 0 }].iter())
+            .chain([Pos::ORIGIN].iter())
             .tuple_windows()
             .map(|(a, b)| ((a.col * b.row) as f64) - ((a.row * b.col) as f64))
             .sum::<f64>()
@@ -61,59 +76,47 @@ impl Plan {
         interior + (boundary / 2) + 1
     }
 
-    pub fn get_path(&self) -> Vec<(&Instruction, SignedRowCol)> {
+    pub fn get_path(&self) -> Vec<(&Instruction, Pos)> {
         let mut path = Vec::new();
-        let mut cur: SignedRowCol = SignedRowCol { row: 0, col: 0 };
+        let mut cur = Pos::ORIGIN;
 
         for instruction in &self.instructions {
             path.push((instruction, cur));
-            cur = cur.move_in_direction(&instruction.direction, instruction.count as isize);
+            cur += instruction.direction.offset() * instruction.count as i64;
         }
 
         path
     }
-}
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct SignedRowCol {
-    row: isize,
-    col: isize,
-}
-
-impl SignedRowCol {
-    pub fn move_in_direction(&self, direction: &Direction, steps: isize) -> SignedRowCol {
-        let row = self.row;
-        let col = self.col;
-        match direction {
-            Direction::Up => SignedRowCol {
-                row: row.sub(steps),
-                col,
-            },
-            Direction::Down => SignedRowCol {
-                row: row + steps,
-                col,
-            },
-            Direction::Left => SignedRowCol {
-                row,
-                col: col.sub(steps),
-            },
-            Direction::Right => SignedRowCol {
-                row,
-                col: col + steps,
-            },
+    /// The trench dug around the edge of the lagoon, with the colour each cubic metre was
+    /// painted. Only practical for the part 1 plan, as the part 2 trench is far too long.
+    pub fn dig_trench(&self) -> SparseGrid<&str> {
+        let mut trench = SparseGrid::new();
+        for (instruction, start) in self.get_path() {
+            let mut pos = start;
+            for _ in 0..instruction.count {
+                pos += instruction.direction.offset();
+                trench.insert(pos, instruction.color.as_str());
+            }
         }
+        trench
     }
 }
 
-struct Instruction {
+pub struct Instruction {
     direction: Direction,
     count: usize,
     color: String,
 }
 
 impl Instruction {
-    pub fn part2(&self) -> Self {
+    pub fn part2(&self) -> Result<Self, anyhow::Error> {
         let color = self.color.as_str();
+        if color.len() != 6 || !color.is_ascii() {
+            return Err(
+                anyhow::Error::msg("Expected a six digit color").context(self.color.clone())
+            );
+        }
         let (count, direction) = color.split_at(5);
 
         let direction = match direction.trim() {
@@ -121,14 +124,17 @@ impl Instruction {
             "1" => Direction::Down,
             "2" => Direction::Left,
             "3" => Direction::Up,
-            _ => panic!("Received invalid direction string: {}", direction),
+            _ => {
+                return Err(anyhow::Error::msg("Received invalid direction string")
+                    .context(direction.to_string()))
+            }
         };
 
-        Instruction {
-            count: usize::from_str_radix(count, 16).unwrap(),
+        Ok(Instruction {
+            count: usize::from_str_radix(count, 16)?,
             direction,
             color: self.color.clone(),
-        }
+        })
     }
 }
 
@@ -151,49 +157,19 @@ impl FromStr for Instruction {
             })?;
 
         Ok(Instruction {
-            direction: direction_from_string(direction)?,
+            direction: direction.parse()?,
             count: count.parse()?,
             color: color.to_string(),
         })
     }
 }
 
-fn direction_from_string(s: &str) -> Result<Direction, anyhow::Error> {
-    match s.trim() {
-        "U" => Ok(Direction::Up),
-        "D" => Ok(Direction::Down),
-        "R" => Ok(Direction::Right),
-        "L" => Ok(Direction::Left),
-        _ => {
-            Err(anyhow::Error::msg("Could not parse direction from string").context(s.to_string()))
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
-    use crate::common::Solution;
-    use crate::day18::{Day18, Day18P2, Instruction};
-    use crate::vec2d::Direction;
-
-    const EXAMPLE_INPUT: &str = r"R 6 (#70c710)
-D 5 (#0dc571)
-L 2 (#5713f0)
-D 2 (#d2c081)
-R 2 (#59c680)
-D 2 (#411b91)
-L 5 (#8ceee2)
-U 2 (#caa173)
-L 1 (#1b58a2)
-U 2 (#caa171)
-R 2 (#7807d2)
-U 3 (#a77fa3)
-L 2 (#015232)
-U 2 (#7a21e3)";
-    #[test]
-    fn test_example() {
-        assert_eq!(Day18::solve(EXAMPLE_INPUT.lines()), "62")
-    }
+    use crate::day18::{Instruction, Plan};
+    use crate::example::example_input;
+    use crate::regions::Connectivity;
+    use crate::vec2d::{Direction, Pos};
 
     #[test]
     fn test_parse() {
@@ -204,7 +180,42 @@ U 2 (#7a21e3)";
     }
 
     #[test]
-    fn test_example_p2() {
-        assert_eq!(Day18P2::solve(EXAMPLE_INPUT.lines()), "952408144115")
+    fn test_dig_trench() {
+        let plan = Plan::from_lines(example_input!("day18/example.txt").iter()).unwrap();
+        let trench = plan.dig_trench();
+        assert_eq!(trench.len(), 38);
+        assert_eq!(trench.get(&Pos::new(0, 1)), Some(&"70c710"));
+        let dug = trench.to_vec2d_with(|color| color.is_some());
+        assert_eq!(
+            dug.render(|&dug| if dug { '#' } else { '.' }).to_string(),
+            "#######
+#.....#
+###...#
+..#...#
+..#...#
+###.###
+#...#..
+##..###
+.#....#
+.######
+"
+        );
+
+        // Everything but the ground outside the trench, which reaches the edge of the map, is dug
+        let outside: usize = dug
+            .label_components(Connectivity::Four)
+            .regions
+            .iter()
+            .filter(|region| !dug.get_row_col(&region.cells()[0]).unwrap())
+            .filter(|region| {
+                let bounds = region.bounding_box().unwrap();
+                bounds.top_left.row == 0
+                    || bounds.top_left.col == 0
+                    || bounds.bottom_right.row == dug.num_rows() - 1
+                    || bounds.bottom_right.col == dug.num_cols() - 1
+            })
+            .map(|region| region.size())
+            .sum();
+        assert_eq!(dug.num_rows() * dug.num_cols() - outside, plan.get_area());
     }
 }
@@ -1,55 +1,94 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use itertools::Itertools;
-
-use crate::common::Solution;
+use crate::common::{parse_lines, split_blocks, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day19 {}
 
-impl Solution for Day19 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let puzzle = Puzzle::from_lines(lines);
-        puzzle
+pub type Day19P2 = Part2<Day19>;
+
+register!(Day19, Day19P2);
+
+impl Puzzle for Day19 {
+    const DAY: u8 = 19;
+    const TITLE: &'static str = "Aplenty";
+
+    type Input = System;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        System::from_lines(lines)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
             .parts
             .iter()
-            .filter(|part| puzzle.get_final_accepted(part))
+            .filter(|part| input.get_final_accepted(part))
             .map(|part| part.ratings.values().sum::<i64>())
             .sum::<i64>()
-            .to_string()
+            .into())
     }
-}
 
-pub enum Day19P2 {}
-impl Solution for Day19P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let puzzle = Puzzle::from_lines(lines);
-        puzzle
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
             .count_satisfying(
                 &PartRange::with_values(Range { min: 1, max: 4000 }),
-                puzzle.workflows.get("in").unwrap(),
+                input.workflows.get("in").unwrap(),
             )
-            .to_string()
+            .into())
     }
 }
 
-struct Puzzle {
+pub struct System {
     workflows: HashMap<String, Workflow>,
     parts: Vec<Part>,
 }
 
-impl Puzzle {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Puzzle {
-        let combined = lines.map(|s| s.as_ref().to_string()).join("\n");
-        let (workflows, parts) = combined.split_once("\n\n").unwrap();
-        Puzzle {
-            workflows: workflows
-                .lines()
-                .map(|line| line.parse::<Workflow>().unwrap())
+impl System {
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<System, SolveError> {
+        let blocks = split_blocks(lines);
+        let [(workflows_offset, workflows), (parts_offset, parts)] = blocks.as_slice() else {
+            return Err(SolveError::msg(
+                "split input",
+                "expected workflows and parts separated by a blank line",
+            ));
+        };
+
+        let workflows: HashMap<String, Workflow> =
+            parse_lines(workflows.iter(), "parse workflow", str::parse::<Workflow>)
+                .map_err(|err| err.offset_line(*workflows_offset))?
+                .into_iter()
                 .map(|workflow| (workflow.name.to_string(), workflow))
-                .collect(),
-            parts: parts.lines().map(|line| line.parse().unwrap()).collect(),
+                .collect();
+        let parts = parse_lines(parts.iter(), "parse part", str::parse)
+            .map_err(|err| err.offset_line(*parts_offset))?;
+
+        if !workflows.contains_key("in") {
+            return Err(SolveError::msg("find workflow", "no workflow named in"));
+        }
+        for workflow in workflows.values() {
+            for next in workflow.next_workflows() {
+                if !workflows.contains_key(next) {
+                    return Err(SolveError::msg(
+                        "find workflow",
+                        format!(
+                            "workflow {} refers to unknown workflow {}",
+                            workflow.name, next
+                        ),
+                    ));
+                }
+            }
         }
+        if let Some(name) = find_loop("in", &workflows, &mut HashMap::new()) {
+            return Err(SolveError::msg(
+                "find workflow",
+                format!("workflow {} can send parts back to itself", name),
+            ));
+        }
+
+        Ok(System { workflows, parts })
     }
 
     fn get_final_accepted(&self, part: &Part) -> bool {
@@ -98,13 +137,48 @@ impl Puzzle {
     }
 }
 
-struct Workflow {
+/// Search the workflows that parts can be sent to from `name` for one that can send parts back to
+/// itself. `visiting` holds true for the workflows on the current path and false for those
+/// already searched.
+fn find_loop<'a>(
+    name: &'a str,
+    workflows: &'a HashMap<String, Workflow>,
+    visiting: &mut HashMap<&'a str, bool>,
+) -> Option<&'a str> {
+    match visiting.get(name) {
+        Some(true) => return Some(name),
+        Some(false) => return None,
+        None => {}
+    }
+    visiting.insert(name, true);
+    for next in workflows[name].next_workflows() {
+        if let Some(found) = find_loop(next, workflows, visiting) {
+            return Some(found);
+        }
+    }
+    visiting.insert(name, false);
+    None
+}
+
+pub struct Workflow {
     name: String,
     rules: Vec<Rule>,
     default_outcome: Outcome,
 }
 
 impl Workflow {
+    /// The names of the workflows this one can send parts to
+    fn next_workflows(&self) -> impl Iterator<Item = &str> {
+        self.rules
+            .iter()
+            .map(|rule| &rule.outcome)
+            .chain([&self.default_outcome])
+            .filter_map(|outcome| match outcome {
+                Outcome::Next(next) => Some(next.as_str()),
+                Outcome::Accepted | Outcome::Rejected => None,
+            })
+    }
+
     pub fn get_outcome(&self, part: &Part) -> &Outcome {
         self.rules
             .iter()
@@ -191,6 +265,9 @@ impl FromStr for Rule {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() < 2 || !s.is_char_boundary(1) || !s.is_char_boundary(2) {
+            return Err(anyhow::Error::msg("Rule is too short").context(s.to_string()));
+        }
         let (category, rest) = s.split_at(1);
         let (condition, rest) = rest.split_at(1);
         let (threshold, outcome) = rest.split_once(':').ok_or_else(|| {
@@ -206,7 +283,7 @@ impl FromStr for Rule {
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
-enum Outcome {
+pub enum Outcome {
     Accepted,
     Rejected,
     Next(String),
@@ -225,7 +302,7 @@ impl FromStr for Outcome {
 }
 
 #[derive(Clone, Eq, PartialEq)]
-struct PartRange {
+pub struct PartRange {
     ratings: HashMap<Category, Range>,
 }
 
@@ -249,7 +326,7 @@ impl PartRange {
 }
 
 #[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
-struct Range {
+pub struct Range {
     min: i64,
     max: i64,
 }
@@ -268,7 +345,7 @@ impl Range {
     }
 }
 
-struct Part {
+pub struct Part {
     ratings: HashMap<Category, i64>,
 }
 
@@ -277,17 +354,21 @@ impl FromStr for Part {
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
         let line = line.trim_start_matches('{').trim_end_matches('}');
-        Ok(Part {
-            ratings: line
-                .split(',')
-                .map(|s| {
-                    let (category, value) = s.split_once('=').ok_or_else(|| {
-                        anyhow::Error::msg("Failed to parse ratings").context(s.to_string())
-                    })?;
-                    Ok((category.parse::<Category>()?, value.parse::<i64>()?))
-                })
-                .collect::<Result<HashMap<_, _>, anyhow::Error>>()?,
-        })
+        let ratings = line
+            .split(',')
+            .map(|s| {
+                let (category, value) = s.split_once('=').ok_or_else(|| {
+                    anyhow::Error::msg("Failed to parse ratings").context(s.to_string())
+                })?;
+                Ok((category.parse::<Category>()?, value.parse::<i64>()?))
+            })
+            .collect::<Result<HashMap<_, _>, anyhow::Error>>()?;
+        if ratings.len() != 4 {
+            return Err(
+                anyhow::Error::msg("Expected a rating for each category").context(line.to_string())
+            );
+        }
+        Ok(Part { ratings })
     }
 }
 
@@ -298,7 +379,7 @@ impl Part {
 }
 
 #[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
-enum Category {
+pub enum Category {
     X,
     M,
     A,
@@ -344,32 +425,30 @@ impl FromStr for Condition {
 #[cfg(test)]
 mod test {
     use crate::common::Solution;
-    use crate::day19::{Day19, Day19P2};
-
-    const EXAMPLE_INPUT: &str = r"px{a<2006:qkq,m>2090:A,rfg}
-pv{a>1716:R,A}
-lnx{m>1548:A,A}
-rfg{s<537:gd,x>2440:R,A}
-qs{s>3448:A,lnx}
-qkq{x<1416:A,crn}
-crn{x>2662:A,R}
-in{s<1351:px,qqz}
-qqz{s>2770:qs,m<1801:hdj,R}
-gd{a>3333:R,R}
-hdj{m>838:A,pv}
-
-{x=787,m=2655,a=1222,s=2876}
-{x=1679,m=44,a=2067,s=496}
-{x=2036,m=264,a=79,s=2244}
-{x=2461,m=1339,a=466,s=291}
-{x=2127,m=1623,a=2188,s=1013}";
+    use crate::day19::Day19;
+    use crate::example::example_input;
+
     #[test]
-    fn test_example() {
-        assert_eq!(Day19::solve(EXAMPLE_INPUT.lines()), "19114")
+    fn test_invalid_part_reports_line() {
+        let input = example_input!("day19/example.txt")
+            .into_iter()
+            .map(|line| line.replace("{x=2036,m=264,a=79,s=2244}", "{x=2036,m=264,a=79}"));
+        let err = Day19::solve(input).err().unwrap();
+        assert_eq!(err.line(), Some(15));
+        assert_eq!(err.step(), "parse part");
     }
 
     #[test]
-    fn test_example_p2() {
-        assert_eq!(Day19P2::solve(EXAMPLE_INPUT.lines()), "167409079868000")
+    fn test_workflow_loop() {
+        let input = [
+            "in{x<10:a,A}",
+            "a{m>5:b,R}",
+            "b{a<3:in,A}",
+            "",
+            "{x=1,m=6,a=2,s=0}",
+        ];
+        let err = Day19::solve(input.into_iter()).err().unwrap();
+        assert_eq!(err.step(), "find workflow");
+        assert!(err.to_string().contains("can send parts back to itself"));
     }
 }
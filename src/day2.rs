@@ -1,53 +1,72 @@
 use std::cmp::max;
 use std::str::FromStr;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day2 {}
-impl Solution for Day2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
+
+pub type Day2P2 = Part2<Day2>;
+
+register!(Day2, Day2P2);
+
+impl Puzzle for Day2 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    type Input = Vec<Game>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_games(lines)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
         let mut sum: u32 = 0;
-        for line in lines {
-            let line = line.as_ref();
-            if line.is_empty() {
-                continue;
-            }
-            let game: Game = line.parse().expect("Could not parse game");
+        for game in input {
             if game.is_feasible() {
                 sum += game.index;
             }
         }
-        sum.to_string()
+        Ok(sum.into())
     }
-}
 
-pub enum Day2P2 {}
-impl Solution for Day2P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|line| line.as_ref().to_string())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.parse::<Game>().unwrap())
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
             .map(|game| {
                 game.sets
-                    .into_iter()
-                    .reduce(|acc, s| Set::minimum_set(&acc, &s))
-                    .unwrap()
+                    .iter()
+                    .fold(Set::default(), |acc, s| Set::minimum_set(&acc, s))
             })
             .map(|set| set.red * set.green * set.blue)
             .sum::<u32>()
-            .to_string()
+            .into())
     }
 }
 
+/// Parses all games in the input, skipping blank lines
+fn parse_games(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Vec<Game>, SolveError> {
+    Ok(parse_lines(lines, "parse game", |line| {
+        if line.is_empty() {
+            Ok(None)
+        } else {
+            line.parse().map(Some)
+        }
+    })?
+    .into_iter()
+    .flatten()
+    .collect())
+}
+
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     index: u32,
     sets: Vec<Set>,
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
-struct Set {
+pub struct Set {
     red: u32,
     green: u32,
     blue: u32,
@@ -59,7 +78,6 @@ impl FromStr for Game {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts = s.split(": ").collect::<Vec<_>>();
         let [game_prefix, set_definitions] = parts.as_slice() else {
-            println!("Parts: {:?}", parts);
             return Err(anyhow::Error::msg("Invalid game received").context(s.to_string()));
         };
 
@@ -150,19 +168,8 @@ impl FromStr for CubeColor {
 
 #[cfg(test)]
 mod test {
-    use crate::day2::*;
 
-    #[test]
-    fn test_example() {
-        let input = r#"
-Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
-
-        assert_eq!(Day2::solve(input.lines()), "8");
-    }
+    use crate::day2::*;
 
     #[test]
     fn test_parse_count_and_color() {
@@ -183,15 +190,4 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
             }
         );
     }
-
-    #[test]
-    fn test_example_part2() {
-        let input = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
-
-        assert_eq!(Day2P2::solve(input.lines()), "2286")
-    }
 }
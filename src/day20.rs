@@ -5,37 +5,60 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::{Param, Params};
+use crate::registry::register;
 
 pub enum Day20 {}
 
-impl Solution for Day20 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut system = System::from_lines(lines);
-        let count: PulseCount = (0..1000).map(|_| system.press_button()).sum::<PulseCount>();
+pub type Day20P2 = Part2<Day20>;
 
-        (count.high * count.low).to_string()
+register!(Day20, Day20P2);
+
+const PRESSES: Param = Param {
+    name: "presses",
+    part: 1,
+    default: "1000",
+    description: "Number of times the button is pushed",
+};
+
+impl Puzzle for Day20 {
+    const DAY: u8 = 20;
+    const TITLE: &'static str = "Pulse Propagation";
+    const PARAMS: &'static [Param] = &[PRESSES];
+
+    type Input = System;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        System::from_lines(lines)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        let mut system = input.clone();
+        let count: PulseCount = (0..params.get::<usize>(&PRESSES)?)
+            .map(|_| system.press_button())
+            .sum::<PulseCount>();
+
+        Ok((count.high * count.low).into())
     }
-}
 
-pub enum Day20P2 {}
-impl Solution for Day20P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let system = System::from_lines(lines);
-        system.presses_until_rx().to_string()
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.clone().presses_until_rx()?.into())
     }
 }
 
-struct System {
+#[derive(Clone)]
+pub struct System {
     modules: HashMap<String, Module>,
 }
 
 impl System {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> System {
-        let mut modules: HashMap<String, Module> = lines
-            .map(|s| s.as_ref().parse::<Module>().unwrap())
-            .map(|module| (module.name.clone(), module))
-            .collect();
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<System, SolveError> {
+        let mut modules: HashMap<String, Module> =
+            parse_lines(lines, "parse module", str::parse::<Module>)?
+                .into_iter()
+                .map(|module| (module.name.clone(), module))
+                .collect();
         let names = modules.keys().map(|s| s.to_string()).collect::<Vec<_>>();
 
         // we need to prepopulate the inputs for the conjunctions so we can check if all are on
@@ -52,7 +75,7 @@ impl System {
             }
         }
 
-        System { modules }
+        Ok(System { modules })
     }
 
     pub fn press_button(&mut self) -> PulseCount {
@@ -81,16 +104,25 @@ impl System {
         count
     }
 
-    pub fn presses_until_rx(mut self) -> usize {
+    pub fn presses_until_rx(mut self) -> Result<usize, SolveError> {
         // inspected the input, rx has exactly one input, that is a conjunction
         let rx_input_name: String = self
             .modules
             .values()
             .filter(|module| module.outputs.iter().any(|s| s == "rx"))
             .exactly_one()
-            .unwrap()
+            .map_err(|_| {
+                SolveError::msg("find rx input", "expected exactly one module to feed rx")
+            })?
             .name
             .clone();
+        let ModuleType::Conjunction(conjunction) = &self.modules[&rx_input_name].module_type else {
+            return Err(SolveError::msg(
+                "find rx input",
+                "expected the module feeding rx to be a conjunction",
+            ));
+        };
+        let conjunction_inputs: Vec<String> = conjunction.inputs.keys().cloned().collect();
 
         let mut counts_until_high: HashMap<String, usize> = HashMap::new();
 
@@ -100,23 +132,18 @@ impl System {
             self.press_button();
             press_count += 1;
 
-            let rx_input = self.modules.get(&rx_input_name).unwrap();
-            let ModuleType::Conjunction(conjunction) = &rx_input.module_type else {
-                unreachable!()
-            };
-
-            for name in conjunction.inputs.keys() {
-                if self.modules.get(name).unwrap().sent_high_count >= 1 {
+            for name in &conjunction_inputs {
+                if self.modules[name].sent_high_count >= 1 {
                     counts_until_high
                         .entry(name.to_string())
                         .or_insert(press_count);
                 }
             }
 
-            if counts_until_high.len() == conjunction.inputs.len() {
-                return counts_until_high
+            if counts_until_high.len() == conjunction_inputs.len() {
+                return Ok(counts_until_high
                     .values()
-                    .fold(1, |a, b| num::integer::lcm(a, *b));
+                    .fold(1, |a, b| num::integer::lcm(a, *b)));
             }
         }
     }
@@ -144,7 +171,7 @@ impl Module {
                 })
                 .collect(),
             ModuleType::Conjunction(conjunction) => {
-                conjunction.inputs.insert(source, pulse).unwrap();
+                conjunction.inputs.insert(source, pulse);
                 let output_pulse = conjunction.get_pulse();
                 self.outputs
                     .iter()
@@ -260,7 +287,7 @@ struct Message {
 }
 
 #[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
-enum Pulse {
+pub enum Pulse {
     High,
     Low,
 }
@@ -272,7 +299,7 @@ impl Pulse {
 }
 
 #[derive(Eq, PartialEq, Hash, Clone, Copy, Debug, Default)]
-struct PulseCount {
+pub struct PulseCount {
     high: usize,
     low: usize,
 }
@@ -303,90 +330,3 @@ impl Sum for PulseCount {
             .unwrap_or(PulseCount::default())
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day20::{Day20, Day20P2};
-
-    const FIRST_EXAMPLE: &str = r"broadcaster -> a, b, c
-%a -> b
-%b -> c
-%c -> inv
-&inv -> a";
-
-    const SECOND_EXAMPLE: &str = r#"broadcaster -> a
-%a -> inv, con
-&inv -> b
-%b -> con
-&con -> output"#;
-
-    #[test]
-    fn test_example() {
-        assert_eq!(Day20::solve(FIRST_EXAMPLE.lines()), "32000000");
-        assert_eq!(Day20::solve(SECOND_EXAMPLE.lines()), "11687500");
-    }
-
-    #[test]
-    fn test_real_p2() {
-        let input = r#"%jb -> fz
-%xz -> ck, bg
-%xm -> qt, cs
-%df -> hc, lq
-%mt -> sx
-%fr -> ks, hc
-%tn -> pf
-%gt -> pp, kb
-%jn -> ck, nz
-%td -> kz
-&rd -> vd
-%pp -> gv, kb
-&qt -> jb, vx, bt, gh, td, gb
-%ms -> xz
-%vx -> fp
-%rb -> ck, mt
-%nz -> hh
-%fp -> rp, qt
-%gd -> gc
-%gv -> kb
-%nl -> cc, hc
-%cs -> qt
-%kz -> jb, qt
-%vg -> fr, hc
-%zq -> qt, xm
-%pv -> ps
-&bt -> vd
-%ps -> kb, rf
-%hh -> ck, ms
-broadcaster -> gn, gb, rb, df
-%gh -> td
-%rf -> kb, nm
-%rp -> qt, gh
-%gc -> kb, pv
-%gb -> vx, qt
-%rq -> ck, ts
-%nm -> gt
-%gn -> kb, tn
-&ck -> nz, fv, rb, sx, ms, mt
-&fv -> vd
-%cc -> vg
-%bg -> ck, rq
-&hc -> qr, ch, df, dj, cc, rd
-%qr -> dj
-%gq -> hc, ch
-&pr -> vd
-%ks -> lc, hc
-%dj -> nl
-%fz -> qt, zq
-%lq -> gq, hc
-&kb -> pv, pr, tn, nm, pf, gn, gd
-%ts -> ck
-%lc -> hc
-%jl -> ck, jn
-%sx -> jl
-%pf -> gd
-&vd -> rx
-%ch -> qr"#;
-        assert_eq!(Day20P2::solve(input.lines()), "228300182686739");
-    }
-}
@@ -1,189 +1,210 @@
-use std::collections::HashSet;
+use std::hash::Hash;
 
-use crate::common::Solution;
-use crate::vec2d::{Cell, Vec2d};
+use crate::animation::Observer;
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::{Param, Params};
+use crate::pathfinding;
+use crate::pathfinding::SearchTree;
+use crate::registry::register;
+use crate::vec2d::{BitGrid, Cell, Direction, Pos, Vec2d};
 
 pub enum Day21 {}
 
-impl Solution for Day21 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let grid = Grid::from_lines(lines);
-        grid.start_reachable_n_steps(64).to_string()
-    }
-}
+pub type Day21P2 = Part2<Day21>;
 
-pub enum Day21P2 {}
-impl Solution for Day21P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let grid = InfiniteGrid::from_lines(lines);
+register!(Day21, Day21P2);
 
-        let grid_len = grid.tiles.num_rows();
+const STEPS: Param = Param {
+    name: "steps",
+    part: 1,
+    default: "64",
+    description: "Number of steps taken from the start",
+};
 
-        let mut reachable = HashSet::new();
-        reachable.insert(grid.get_start().into());
+const TOTAL_STEPS: Param = Param {
+    name: "total_steps",
+    part: 2,
+    default: "26501365",
+    description: "Number of steps taken from the start across the infinitely repeated map",
+};
 
-        // the total steps is 26501365 = grid_len * 202300 + 65
-        // we want to get past the first 65 steps and then use a quadratic equation at each grid
-        // boundary. The quadratic pattern is from someone on reddit.
-        for i in 0..=327 {
-            if (i - 65) % grid_len == 0 {
-                println!("{}, {}", i, reachable.len());
-            }
-            reachable = grid.one_step_reachable(reachable.iter());
-        }
+impl Puzzle for Day21 {
+    const DAY: u8 = 21;
+    const TITLE: &'static str = "Step Counter";
+    const PARAMS: &'static [Param] = &[STEPS, TOTAL_STEPS];
 
-        // take the printed values, use an online quadratic regression, and with values 1, 2, 3
-        // then put in 202300 as the input.
+    type Input = Grid;
 
-        "".to_string()
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Grid::from_lines(lines)
     }
-}
 
-struct Grid {
-    tiles: Vec2d<Tile>,
-}
-
-impl Grid {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Self {
-        Grid {
-            tiles: Vec2d::from_lines(lines).map(|&c| c.into()),
-        }
-    }
-
-    pub fn get_start(&self) -> Cell<Tile> {
-        self.tiles
-            .cells()
-            .find(|cell| cell.value().is_start())
-            .unwrap()
+    fn part1(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.start_reachable_n_steps(params.get(&STEPS)?).into())
     }
 
-    pub fn one_step_reachable<'a>(
-        &'a self,
-        tiles: impl Iterator<Item = Cell<'a, Tile>>,
-    ) -> HashSet<Cell<'a, Tile>> {
-        let mut set = HashSet::new();
-        for tile in tiles {
-            tile.cardinal_neighbors()
-                .filter(|cell| !cell.is_rock())
-                .for_each(|cell| {
-                    set.insert(self.tiles.get_cell(cell.row(), cell.col()).unwrap());
-                });
+    fn part2(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        let total_steps: usize = params.get(&TOTAL_STEPS)?;
+        input.check_repeating_shape(total_steps)?;
+
+        // The walk ends at the edge of a copy of the map: 26501365 = 131 * 202300 + 65. With the
+        // start in the middle and clear paths along its row and column, the number of reachable
+        // plots grows quadratically with the number of maps crossed, so count it after crossing
+        // 0, 1 and 2 maps and extrapolate.
+        let grid_len = input.tiles.num_rows();
+        let maps = total_steps / grid_len;
+        if maps <= 2 {
+            return Ok(input.start_reachable_n_steps_tiled(total_steps).into());
         }
-        set
+        let first_steps = total_steps % grid_len;
+        let samples = [0, 1, 2].map(|crossed| {
+            input.start_reachable_n_steps_tiled(first_steps + crossed * grid_len) as i64
+        });
+        Ok(extrapolate_quadratic(samples, maps as i64).into())
     }
+}
 
-    fn start_reachable_n_steps(&self, steps: usize) -> usize {
-        let mut reachable = HashSet::new();
-        reachable.insert(self.get_start());
-
-        for _ in 0..steps {
-            reachable = self.one_step_reachable(reachable.iter().cloned());
-        }
+/// The value at `x` of the quadratic taking the values `samples` at 0, 1 and 2
+fn extrapolate_quadratic(samples: [i64; 3], x: i64) -> i64 {
+    let [a, b, c] = samples;
+    let first_difference = b - a;
+    let second_difference = c - 2 * b + a;
+    a + x * first_difference + x * (x - 1) / 2 * second_difference
+}
 
-        reachable.len()
+/// Parse the garden map, checking that it has a starting tile
+fn parse_garden(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Vec2d<Tile>, SolveError> {
+    let tiles = parse_grid(lines, "parse garden tile", Tile::try_from)?;
+    if !tiles.cells().any(|cell| cell.value().is_start()) {
+        return Err(SolveError::msg("find start", "no S tile in input"));
     }
+    Ok(tiles)
 }
 
-struct InfiniteGrid {
+pub struct Grid {
     tiles: Vec2d<Tile>,
 }
 
-impl InfiniteGrid {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Self {
-        InfiniteGrid {
-            tiles: Vec2d::from_lines(lines).map(|&c| c.into()),
-        }
+impl Grid {
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self, SolveError> {
+        Ok(Grid {
+            tiles: parse_garden(lines)?,
+        })
     }
 
-    pub fn get_start(&self) -> Cell<Tile> {
+    pub fn get_start(&self) -> Cell<'_, Tile> {
         self.tiles
             .cells()
             .find(|cell| cell.value().is_start())
             .unwrap()
     }
 
-    pub fn one_step_reachable<'a>(
-        &self,
-        points: impl Iterator<Item = &'a Point>,
-    ) -> HashSet<Point> {
-        let mut set = HashSet::new();
-        for point in points {
-            point
-                .neighbors()
-                .iter()
-                .filter(|p| !self.get_value_on_grid(p).is_rock())
-                .for_each(|&p| {
-                    set.insert(p);
-                })
-        }
-        set
+    /// Count the plots that can be reached in exactly `steps` steps
+    fn start_reachable_n_steps(&self, steps: usize) -> usize {
+        self.start_reachable_n_steps_observed(steps, &mut ())
     }
 
-    #[allow(dead_code)]
-    pub fn start_reachable_n_steps(&self, steps: usize) -> usize {
-        let mut reachable = HashSet::new();
-        reachable.insert(self.get_start().into());
-
-        for _ in 0..steps {
-            reachable = self.one_step_reachable(reachable.iter());
+    /// Count the plots that can be reached in exactly `steps` steps, showing `observer` the plots
+    /// reachable in exactly 0, 1 and so on up to `steps` steps
+    pub fn start_reachable_n_steps_observed(
+        &self,
+        steps: usize,
+        observer: &mut impl Observer<BitGrid>,
+    ) -> usize {
+        // The plots reachable in exactly one more step are the neighbours of the plots reachable
+        // now, so each step shifts the reachable set every way and masks out the rocks
+        let garden = BitGrid::from(&self.tiles.map(|tile| !tile.is_rock()));
+        let mut reachable = BitGrid::new(self.tiles.num_rows(), self.tiles.num_cols());
+        reachable.set(&self.get_start().coords(), true);
+        let mut shifted = reachable.clone();
+        let mut next = reachable.clone();
+        for step in 0..=steps {
+            if step > 0 {
+                next.clear();
+                for direction in Direction::ALL {
+                    shifted.shift_from(&reachable, direction);
+                    next.union_with(&shifted);
+                }
+                next.intersect_with(&garden);
+                std::mem::swap(&mut reachable, &mut next);
+            }
+            observer.observe(&reachable);
         }
-
-        reachable.len()
-    }
-
-    fn get_value_on_grid(&self, point: &Point) -> Tile {
-        let row = point.row.rem_euclid(self.tiles.num_rows() as i64);
-        let col = point.col.rem_euclid(self.tiles.first_num_cols() as i64);
-        *self.tiles.get(row as usize, col as usize).unwrap()
+        reachable.count_ones()
     }
-}
 
-#[derive(Eq, PartialEq, Hash, Clone, Copy)]
-struct Point {
-    row: i64,
-    col: i64,
-}
-
-impl Point {
-    pub fn neighbors(&self) -> [Point; 4] {
-        [self.above(), self.right(), self.below(), self.left()]
-    }
-
-    fn diff(&self, row_diff: i64, col_diff: i64) -> Point {
-        Point {
-            row: self.row + row_diff,
-            col: self.col + col_diff,
+    /// Check that the map has the layout that makes the reachable plots grow quadratically
+    /// with the number of copies of the map crossed: a square map with the start in the middle,
+    /// no rocks in the row and column of the start, and a walk of `total_steps` that ends at the
+    /// edge of a copy of the map
+    pub fn check_repeating_shape(&self, total_steps: usize) -> Result<(), SolveError> {
+        let step = "check map shape";
+        let size = self.tiles.num_rows();
+        if self.tiles.num_cols() != size {
+            return Err(SolveError::msg(step, "the map is not square"));
         }
+        let start = self.get_start();
+        if start.row() != size / 2 || start.col() != size / 2 || size.is_multiple_of(2) {
+            return Err(SolveError::msg(
+                step,
+                "the start is not in the middle of the map",
+            ));
+        }
+        let row_clear = !start.get_row().iter().any(Tile::is_rock);
+        let col_clear = !self.tiles.get_col(start.col()).any(Tile::is_rock);
+        if !row_clear || !col_clear {
+            return Err(SolveError::msg(
+                step,
+                "the row and column of the start are not clear of rocks",
+            ));
+        }
+        if total_steps % size != size / 2 {
+            return Err(SolveError::msg(
+                step,
+                format!(
+                    "{} steps does not end at the edge of a copy of the map",
+                    total_steps
+                ),
+            ));
+        }
+        Ok(())
     }
 
-    fn above(&self) -> Point {
-        self.diff(-1, 0)
-    }
-
-    fn below(&self) -> Point {
-        self.diff(1, 0)
-    }
-
-    fn left(&self) -> Point {
-        self.diff(0, -1)
+    /// Count the plots that can be reached in exactly `steps` steps, in any copy of the map
+    /// repeated infinitely in every direction
+    pub fn start_reachable_n_steps_tiled(&self, steps: usize) -> usize {
+        let tiled = self.tiles.tiled();
+        let start = Pos::from(self.get_start().coords());
+        let tree = pathfinding::bfs_within(start, steps, |pos| {
+            tiled
+                .cardinal_neighbors(pos)
+                .filter(|next| !tiled.get(next).is_rock())
+                .collect::<Vec<_>>()
+        });
+        count_reachable(&tree, steps)
     }
 
-    fn right(&self) -> Point {
-        self.diff(0, 1)
+    /// The map with the `reachable` plots marked `O`, as in the puzzle description
+    pub fn frame(&self, reachable: &BitGrid) -> Vec2d<char> {
+        let mut frame = self.tiles.map(|&tile| char::from(tile));
+        for coords in reachable.ones() {
+            *frame.get_mut(coords.row, coords.col).unwrap() = 'O';
+        }
+        frame
     }
 }
 
-impl<'a, T> From<Cell<'a, T>> for Point {
-    fn from(value: Cell<'a, T>) -> Self {
-        Point {
-            row: value.row() as i64,
-            col: value.col() as i64,
-        }
-    }
+/// Count the states that can be reached in exactly `steps` steps. A state reached in fewer steps
+/// can still be reached at the end by stepping back and forth, as long as the number of steps
+/// left over is even.
+fn count_reachable<S: Clone + Eq + Hash>(tree: &SearchTree<S, usize>, steps: usize) -> usize {
+    tree.costs()
+        .filter(|&(_, distance)| distance <= steps && (steps - distance).is_multiple_of(2))
+        .count()
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
-enum Tile {
+pub enum Tile {
     Starting,
     Garden,
     Rock,
@@ -199,43 +220,121 @@ impl Tile {
     }
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            'S' => Tile::Starting,
-            '.' => Tile::Garden,
-            '#' => Tile::Rock,
-            _ => panic!("Could not convert char to tile: {}", value),
+            'S' => Ok(Tile::Starting),
+            '.' => Ok(Tile::Garden),
+            '#' => Ok(Tile::Rock),
+            _ => Err(anyhow::Error::msg("Could not convert char to tile").context(value)),
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(tile: Tile) -> char {
+        match tile {
+            Tile::Starting => 'S',
+            Tile::Garden => '.',
+            Tile::Rock => '#',
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day21::{Grid, InfiniteGrid};
+    use crate::animation::Animation;
+    use crate::common::Puzzle;
+    use crate::day21::{extrapolate_quadratic, Day21, Grid};
+    use crate::example::example_input;
+    use crate::params::Params;
+    use crate::vec2d::BitGrid;
+
+    #[test]
+    fn test_example_p2() {
+        let grid = Grid::from_lines(example_input!("day21/six-steps.txt").iter()).unwrap();
+        assert_eq!(grid.start_reachable_n_steps_tiled(6), 16);
+        assert_eq!(grid.start_reachable_n_steps_tiled(10), 50);
+        assert_eq!(grid.start_reachable_n_steps_tiled(50), 1594);
+    }
 
-    const EXAMPLE_INPUT: &str = r"...........
+    #[test]
+    fn test_record_steps() {
+        let grid = Grid::from_lines(example_input!("day21/six-steps.txt").iter()).unwrap();
+        let mut animation = Animation::new();
+        let reachable = grid.start_reachable_n_steps_observed(
+            6,
+            &mut animation.recorder(|reachable: &BitGrid| grid.frame(reachable)),
+        );
+        assert_eq!(reachable, 16);
+        assert_eq!(animation.len(), 7);
+        assert_eq!(
+            animation.frames()[6].render(|&c| c).to_string(),
+            "...........
 .....###.#.
-.###.##..#.
-..#.#...#..
-....#.#....
-.##..S####.
-.##..#...#.
-.......##..
+.###.##.O#.
+.O#O#O.O#..
+O.O.#.#.O..
+.##O.O####.
+.##.O#O..#.
+.O.O.O.##..
 .##.#.####.
-.##..##.##.
+.##O.##.##.
+...........
+"
+        );
+    }
+
+    /// A map with the layout of the real inputs: the start in the middle, with clear paths along
+    /// its row and column and around the edge
+    const REPEATING_INPUT: &str = r"...........
+.#......##.
+..#.....#..
+....#......
+.##.....#..
+.....S.....
+..#.....##.
+.#.........
+...#...#.#.
+.#.....#...
 ...........";
+
+    #[test]
+    fn test_p2_extrapolated() {
+        let grid = Grid::from_lines(REPEATING_INPUT.lines()).unwrap();
+        for total_steps in [5, 27, 49, 71] {
+            let mut params = Params::default();
+            params.set("total_steps", total_steps.to_string());
+            assert_eq!(
+                Day21::part2(&grid, &params).unwrap().to_string(),
+                grid.start_reachable_n_steps_tiled(total_steps).to_string(),
+                "{} steps",
+                total_steps
+            );
+        }
+    }
+
     #[test]
-    fn test_example() {
-        let grid = Grid::from_lines(EXAMPLE_INPUT.lines());
-        assert_eq!(grid.start_reachable_n_steps(6), 16);
+    fn test_p2_rejects_other_layouts() {
+        let grid = Grid::from_lines(["..#", ".S#", "..."].into_iter()).unwrap();
+        let mut params = Params::default();
+        params.set("total_steps", "4");
+        let err = Day21::part2(&grid, &params).unwrap_err();
+        assert!(err.to_string().contains("row and column of the start"));
+
+        let grid = Grid::from_lines(REPEATING_INPUT.lines()).unwrap();
+        params.set("total_steps", "50");
+        let err = Day21::part2(&grid, &params).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("50 steps does not end at the edge"));
     }
 
     #[test]
-    fn test_example_p2() {
-        let grid = InfiniteGrid::from_lines(EXAMPLE_INPUT.lines());
-        assert_eq!(grid.start_reachable_n_steps(6), 16);
-        assert_eq!(grid.start_reachable_n_steps(10), 50);
-        assert_eq!(grid.start_reachable_n_steps(50), 1594);
+    fn test_extrapolate_quadratic() {
+        let f = |x: i64| 3 * x * x - 2 * x + 7;
+        assert_eq!(extrapolate_quadratic([f(0), f(1), f(2)], 202300), f(202300));
     }
 }
@@ -5,37 +5,50 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day22 {}
 
-impl Solution for Day22 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut snapshot = Snapshot::from_lines(lines);
+pub type Day22P2 = Part2<Day22>;
+
+register!(Day22, Day22P2);
+
+impl Puzzle for Day22 {
+    const DAY: u8 = 22;
+    const TITLE: &'static str = "Sand Slabs";
+
+    /// The snapshot after every brick has settled, which both parts start from
+    type Input = Snapshot;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        let mut snapshot = Snapshot::from_lines(lines)?;
         snapshot.drop_bricks();
-        (snapshot.bricks.len() - snapshot.count_sole_supporting()).to_string()
+        Ok(snapshot)
     }
-}
 
-pub enum Day22P2 {}
-impl Solution for Day22P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut snapshot = Snapshot::from_lines(lines);
-        snapshot.drop_bricks();
-        snapshot.get_maximal_disintegration_count().to_string()
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok((input.bricks.len() - input.count_sole_supporting()).into())
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.get_maximal_disintegration_count().into())
     }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
-struct Snapshot {
+pub struct Snapshot {
     bricks: Vec<Brick>,
 }
 
 impl Snapshot {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Snapshot {
-        Snapshot {
-            bricks: lines.map(|line| line.as_ref().parse().unwrap()).collect(),
-        }
+    pub fn from_lines(
+        lines: impl Iterator<Item = impl AsRef<str>>,
+    ) -> Result<Snapshot, SolveError> {
+        Ok(Snapshot {
+            bricks: parse_lines(lines, "parse brick", str::parse)?,
+        })
     }
 
     fn get_supporters(&self) -> Vec<HashSet<usize>> {
@@ -261,7 +274,9 @@ impl FromStr for Brick {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first, second) = s.split_once('~').unwrap();
+        let (first, second) = s.split_once('~').ok_or_else(|| {
+            anyhow::Error::msg("Could not split brick ends").context(s.to_string())
+        })?;
         Ok(Brick {
             first: first.parse()?,
             second: second.parse()?,
@@ -332,12 +347,14 @@ impl FromStr for Position {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, rest) = s.split_once(',').unwrap();
-        let (y, z) = rest.split_once(',').unwrap();
+        let parts: Vec<_> = s.split(',').collect();
+        let [x, y, z] = parts.as_slice() else {
+            return Err(anyhow::Error::msg("Expected three coordinates").context(s.to_string()));
+        };
         Ok(Position {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-            z: z.parse().unwrap(),
+            x: x.parse()?,
+            y: y.parse()?,
+            z: z.parse()?,
         })
     }
 }
@@ -348,20 +365,8 @@ mod test {
 
     use itertools::Itertools;
 
-    use crate::common::Solution;
-    use crate::day22::{Brick, Day22, Day22P2, Snapshot};
-
-    const EXAMPLE_INPUT: &str = r"1,0,1~1,2,1
-0,0,2~2,0,2
-0,2,3~2,2,3
-0,0,4~0,2,4
-2,0,5~2,2,5
-0,1,6~2,1,6
-1,1,8~1,1,9";
-    #[test]
-    fn test_example() {
-        assert_eq!(Day22::solve(EXAMPLE_INPUT.lines()), "5")
-    }
+    use crate::day22::{Brick, Snapshot};
+    use crate::example::example_input;
 
     #[test]
     fn test_intersects() {
@@ -381,15 +386,10 @@ mod test {
     }
     #[test]
     fn test_intersects_against_naive() {
-        let snapshot = Snapshot::from_lines(EXAMPLE_INPUT.lines());
+        let snapshot = Snapshot::from_lines(example_input!("day22/example.txt").iter()).unwrap();
         for (a, b) in snapshot.bricks.iter().tuple_combinations() {
             assert_eq!(a.intersects(b), a.intersects_naive(b));
             assert_eq!(b.intersects(a), a.intersects(b))
         }
     }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day22P2::solve(EXAMPLE_INPUT.lines()), "7")
-    }
 }
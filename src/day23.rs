@@ -1,37 +1,49 @@
-use std::cell::RefCell;
 use std::collections::HashSet;
-use std::rc::Rc;
 
-use crate::common::Solution;
-use crate::vec2d::{Cell, Direction, RowCol, Vec2d};
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
+use crate::vec2d::{BitGrid, Cell, Direction, RowCol, Vec2d};
 
 pub enum Day23 {}
 
-impl Solution for Day23 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let trail = Trail::from_lines(lines);
-        trail.get_max_path().to_string()
+pub type Day23P2 = Part2<Day23>;
+
+register!(Day23, Day23P2);
+
+impl Puzzle for Day23 {
+    const DAY: u8 = 23;
+    const TITLE: &'static str = "A Long Walk";
+
+    type Input = Trail;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Trail::from_lines(lines)
     }
-}
 
-pub enum Day23P2 {}
-impl Solution for Day23P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let trail = Trail::from_lines(lines);
-        trail.get_max_path_p2().to_string()
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.get_max_path().into())
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input.get_max_path_p2().into())
     }
 }
 
-struct Trail {
+pub struct Trail {
     tiles: Vec2d<Tile>,
 }
 
 impl Trail {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Trail {
-        let grid = Vec2d::from_lines(lines);
-        Trail {
-            tiles: grid.map(|&c| c.into()),
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Trail, SolveError> {
+        let tiles = parse_grid(lines, "parse trail", Tile::try_from)?;
+        let has_start = tiles
+            .get_row(0)
+            .is_some_and(|row| row.iter().any(Tile::is_empty));
+        if !has_start {
+            return Err(SolveError::msg("find start", "no path tile in the first row").on_line(1));
         }
+        Ok(Trail { tiles })
     }
 
     pub fn get_start(&self) -> RowCol {
@@ -73,45 +85,29 @@ impl Trail {
     }
 
     pub fn get_max_path_p2(&self) -> usize {
-        self.get_max_path_inner_p2(
-            &self.get_start(),
-            Rc::new(RefCell::new(self.tiles.map(|_| false))),
-        )
-        .unwrap()
+        let mut visited = BitGrid::new(self.tiles.num_rows(), self.tiles.num_cols());
+        self.get_max_path_inner_p2(&self.get_start(), &mut visited)
+            .unwrap_or(0)
     }
 
-    fn get_max_path_inner_p2(
-        &self,
-        start: &RowCol,
-        visited: Rc<RefCell<Vec2d<bool>>>,
-    ) -> Option<usize> {
-        let Some(cell) = self.tiles.get_cell(start.row, start.col) else {
-            return None;
-        };
+    fn get_max_path_inner_p2(&self, start: &RowCol, visited: &mut BitGrid) -> Option<usize> {
+        let cell = self.tiles.get_cell(start.row, start.col)?;
 
         if cell.is_end() {
             return Some(0);
         }
 
-        {
-            let mut visited = visited.borrow_mut();
-            let cur_visited = visited.get_mut(start.row, start.col).unwrap();
-            if *cur_visited {
-                return None;
-            } else {
-                *cur_visited = true;
-            }
+        if visited.set(start, true) {
+            return None;
         }
 
         let result = cell
             .next_tiles_p2()
             .into_iter()
-            .filter(|coords| !visited.borrow().get_row_col(coords).unwrap())
-            .flat_map(|coords| self.tiles.get_cell(coords.row, coords.col))
-            .filter_map(|cell| self.get_max_path_inner_p2(&cell.coords(), visited.clone()))
+            .filter_map(|coords| self.get_max_path_inner_p2(&coords, visited))
             .max()
             .map(|val| val + 1);
-        *visited.borrow_mut().get_mut(start.row, start.col).unwrap() = false;
+        visited.set(start, false);
         result
     }
 }
@@ -166,55 +162,15 @@ impl<'a> Cell<'a, Tile> {
     }
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Tile::Empty,
-            '#' => Tile::Forest,
-            '>' => Tile::Slope(Direction::Right),
-            '<' => Tile::Slope(Direction::Left),
-            'v' => Tile::Slope(Direction::Down),
-            '^' => Tile::Slope(Direction::Up),
-            _ => panic!("Invalid char to tile: {}", value),
+            '.' => Ok(Tile::Empty),
+            '#' => Ok(Tile::Forest),
+            '>' | '<' | 'v' | '^' => Ok(Tile::Slope(Direction::try_from(value)?)),
+            _ => Err(anyhow::Error::msg("Invalid char to tile").context(value)),
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day23::{Day23, Day23P2};
-
-    const EXAMPLE_INPUT: &str = r"#.#####################
-#.......#########...###
-#######.#########.#.###
-###.....#.>.>.###.#.###
-###v#####.#v#.###.#.###
-###.>...#.#.#.....#...#
-###v###.#.#.#########.#
-###...#.#.#.......#...#
-#####.#.#.#######.#.###
-#.....#.#.#.......#...#
-#.#####.#.#.#########v#
-#.#...#...#...###...>.#
-#.#.#v#######v###.###v#
-#...#.>.#...>.>.#.###.#
-#####v#.#.###v#.#.###.#
-#.....#...#...#.#.#...#
-#.#########.###.#.#.###
-#...###...#...#...#.###
-###.###.#.###v#####v###
-#...#...#.#.>.>.#.>.###
-#.###.###.#.###.#.#v###
-#.....###...###...#...#
-#####################.#";
-    #[test]
-    fn test_example() {
-        assert_eq!(Day23::solve(EXAMPLE_INPUT.lines()), "94")
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day23P2::solve(EXAMPLE_INPUT.lines()), "154")
-    }
-}
@@ -4,20 +4,15 @@ use itertools::Itertools;
 use nalgebra::{Matrix, SMatrix, Vector3, Vector6};
 use num::Zero;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::{Param, Params};
+use crate::registry::register;
 
 pub enum Day24 {}
 
-impl Solution for Day24 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let hailstones = Hailstones::from_lines(lines);
-        hailstones
-            .forward_collisions_within_xy_range(200000000000000.0, 400000000000000.0)
-            .to_string()
-    }
-}
+pub type Day24P2 = Part2<Day24>;
 
-pub enum Day24P2 {}
+register!(Day24, Day24P2);
 
 macro_rules! set_view {
     ($view:expr, $other:expr) => {
@@ -27,10 +22,48 @@ macro_rules! set_view {
     };
 }
 
-impl Solution for Day24P2 {
-    // this is basically totally copied from https://www.reddit.com/r/adventofcode/comments/18pnycy/comment/kepu26z
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let hailstones = Hailstones::from_lines(lines).hailstones;
+const MIN_COORDINATE: Param = Param {
+    name: "min",
+    part: 1,
+    default: "200000000000000",
+    description: "Lower bound of the test area on the X and Y axes",
+};
+
+const MAX_COORDINATE: Param = Param {
+    name: "max",
+    part: 1,
+    default: "400000000000000",
+    description: "Upper bound of the test area on the X and Y axes",
+};
+
+impl Puzzle for Day24 {
+    const DAY: u8 = 24;
+    const TITLE: &'static str = "Never Tell Me The Odds";
+    const PARAMS: &'static [Param] = &[MIN_COORDINATE, MAX_COORDINATE];
+
+    type Input = Hailstones;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Hailstones::from_lines(lines)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .forward_collisions_within_xy_range(
+                params.get(&MIN_COORDINATE)?,
+                params.get(&MAX_COORDINATE)?,
+            )
+            .into())
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let hailstones = &input.hailstones;
+        if hailstones.len() < 3 {
+            return Err(SolveError::msg(
+                "solve rock trajectory",
+                "need at least three hailstones",
+            ));
+        }
         let mut matrix: SMatrix<f64, 6, 6> = Matrix::zero();
         let mut rhs: Vector6<f64> = Matrix::zero();
         *rhs.fixed_rows_mut::<3>(0) = *(-hailstones[0].position.cross(&hailstones[0].velocity)
@@ -64,22 +97,25 @@ impl Solution for Day24P2 {
             set_view!(view, values);
         }
 
-        let result = matrix.try_inverse().unwrap() * rhs;
+        let inverse = matrix.try_inverse().ok_or_else(|| {
+            SolveError::msg("solve rock trajectory", "hailstone system is singular")
+        })?;
+        let result = inverse * rhs;
         let position: Vector3<f64> = Vector3::new(result.x, result.y, result.z);
 
-        (position.x + position.y + position.z).round().to_string()
+        Ok(((position.x + position.y + position.z).round() as i64).into())
     }
 }
 
-struct Hailstones {
+pub struct Hailstones {
     hailstones: Vec<PositionVelocity>,
 }
 
 impl Hailstones {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Self {
-        Hailstones {
-            hailstones: lines.map(|line| line.as_ref().parse().unwrap()).collect(),
-        }
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self, SolveError> {
+        Ok(Hailstones {
+            hailstones: parse_lines(lines, "parse hailstone", str::parse)?,
+        })
     }
 
     pub fn forward_collisions_within_xy_range(&self, min_val: f64, max_val: f64) -> usize {
@@ -198,26 +234,3 @@ impl FromStr for PositionVelocity {
 fn approx_equal(a: f64, b: f64) -> bool {
     (a - b).abs() < 0.000000001
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day24::{Day24P2, Hailstones};
-
-    const EXAMPLE_INPUT: &str = r"19, 13, 30 @ -2,  1, -2
-18, 19, 22 @ -1, -1, -2
-20, 25, 34 @ -2, -2, -4
-12, 31, 28 @ -1, -2, -1
-20, 19, 15 @  1, -5, -3";
-    #[test]
-    fn test_example() {
-        let hailstones = Hailstones::from_lines(EXAMPLE_INPUT.lines());
-
-        assert_eq!(hailstones.forward_collisions_within_xy_range(7.0, 27.0), 2)
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day24P2::solve(EXAMPLE_INPUT.lines()), "47")
-    }
-}
@@ -7,35 +7,92 @@ use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::{Rng, SeedableRng};
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Puzzle, SolveError};
+use crate::params::Params;
+use crate::regions;
+use crate::registry::register;
 
 pub enum Day25 {}
 
-impl Solution for Day25 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let graph = Graph::from_lines(lines);
+register!(Day25);
+
+impl Puzzle for Day25 {
+    const DAY: u8 = 25;
+    const TITLE: &'static str = "Snowverload";
+
+    type Input = Graph;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Graph::from_lines(lines)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        if input.get_nodes().len() < 2 {
+            return Err(SolveError::msg("cut graph", "need at least two components"));
+        }
+        if !input.is_connected() {
+            return Err(SolveError::msg(
+                "cut graph",
+                "the components are not all connected to each other",
+            ));
+        }
         let mut rng = StdRng::seed_from_u64(1);
-        loop {
-            let result = graph.clone().karger(&mut rng);
+        for _ in 0..MAX_ATTEMPTS {
+            let Some(result) = input.karger(&mut rng) else {
+                break;
+            };
             if result.remaining_edges.len() == 3 {
-                return (result.partitions.0.len() * result.partitions.1.len()).to_string();
+                return Ok((result.partitions.0.len() * result.partitions.1.len()).into());
             }
         }
+        Err(SolveError::msg(
+            "cut graph",
+            format!(
+                "found no cut of exactly three wires in {} attempts",
+                MAX_ATTEMPTS
+            ),
+        ))
     }
 }
+
+/// The number of random contractions to try before giving up on finding a cut of three wires.
+/// Each attempt finds the smallest cut with a probability of at least 2 / n^2 for n components,
+/// and much more often for graphs made of two clusters joined by a few wires.
+const MAX_ATTEMPTS: usize = 10_000;
+
 #[derive(Clone)]
-struct Graph {
+pub struct Graph {
     edges: Vec<Edge>,
 }
 
 impl Graph {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Graph {
-        Graph {
-            edges: lines.flat_map(|s| Edge::parse_line(s.as_ref())).collect(),
+    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Graph, SolveError> {
+        Ok(Graph {
+            edges: parse_lines(lines, "parse connections", Edge::parse_line)?
+                .into_iter()
+                .flatten()
+                .collect(),
+        })
+    }
+
+    /// Whether every component can be reached from every other one
+    pub fn is_connected(&self) -> bool {
+        let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for Edge(a, b) in &self.edges {
+            neighbors.entry(a).or_default().push(b);
+            neighbors.entry(b).or_default().push(a);
         }
+        let Some(&start) = neighbors.keys().next() else {
+            return true;
+        };
+        let reached = regions::flood_fill_by(start, |node| neighbors[node].clone());
+        reached.len() == neighbors.len()
     }
 
-    pub fn karger<R>(&self, rng: &mut R) -> KargerResult
+    /// Randomly contract the graph into two groups of components, returning the groups and the
+    /// edges between them, or `None` if the graph is not connected and can't be contracted to
+    /// two groups
+    pub fn karger<R>(&self, rng: &mut R) -> Option<KargerResult>
     where
         R: Rng + Sized,
     {
@@ -75,7 +132,6 @@ impl Graph {
             supernodes.union(first.as_str(), second.as_str());
             num_nodes -= 1;
         }
-        debug_assert_eq!(num_nodes, 2);
 
         let remaining_edges: Vec<Edge> = self
             .edges
@@ -93,20 +149,13 @@ impl Graph {
             .into_iter()
             .into_group_map_by(|node| supernodes.find_root(node.as_str()).as_str().to_string());
 
-        if partitions.len() != 2 {
-            panic!("Did not receive exactly two partitions: {:?}", partitions);
-        }
-
-        let partition_values = partitions.values().collect::<Vec<_>>();
-
-        let [first, second] = partition_values.as_slice() else {
-            panic!("Did not receive exactly two groups: {:?}", partitions);
-        };
+        let partition_values = partitions.into_values().collect::<Vec<_>>();
+        let [first, second] = <[_; 2]>::try_from(partition_values).ok()?;
 
-        KargerResult {
-            partitions: ((*first).clone(), (*second).clone()),
+        Some(KargerResult {
+            partitions: (first, second),
             remaining_edges,
-        }
+        })
     }
 
     fn get_nodes(&self) -> Vec<String> {
@@ -118,7 +167,7 @@ impl Graph {
     }
 }
 
-struct KargerResult {
+pub struct KargerResult {
     partitions: (Vec<String>, Vec<String>),
     remaining_edges: Vec<Edge>,
 }
@@ -177,37 +226,37 @@ struct SuperNode {
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
 struct Edge(String, String);
 impl Edge {
-    pub fn parse_line(line: &str) -> Vec<Edge> {
-        let (name, nodes) = line.split_once(": ").unwrap();
+    pub fn parse_line(line: &str) -> anyhow::Result<Vec<Edge>> {
+        let (name, nodes) = line.split_once(": ").ok_or_else(|| {
+            anyhow::Error::msg("Could not split component and connections")
+                .context(line.to_string())
+        })?;
 
-        nodes
+        Ok(nodes
             .trim()
             .split_ascii_whitespace()
             .map(|s| Edge(name.to_string(), s.to_string()))
-            .collect()
+            .collect())
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::common::Solution;
-    use crate::day25::Day25;
-
-    const EXAMPLE_INPUT: &str = r"jqt: rhn xhk nvd
-rsh: frs pzl lsr
-xhk: hfx
-cmg: qnr nvd lhk bvb
-rhn: xhk bvb hfx
-bvb: xhk hfx
-pzl: lsr hfx nvd
-qnr: nvd
-ntq: jqt hfx bvb xhk
-nvd: lhk
-lsr: lhk
-rzs: qnr cmg lsr rsh
-frs: qnr lhk lsr";
+    use crate::day25::{Day25, Graph};
+    #[test]
+    fn test_disconnected() {
+        let input = ["a: b", "c: d", "e: f"];
+        let err = Day25::solve(input.into_iter()).unwrap_err();
+        assert!(err.to_string().contains("not all connected"));
+        let graph = Graph::from_lines(input.into_iter()).unwrap();
+        assert!(graph.karger(&mut rand::thread_rng()).is_none());
+    }
+
     #[test]
-    fn test_example() {
-        assert_eq!(Day25::solve(EXAMPLE_INPUT.lines()), "54")
+    fn test_no_three_wire_cut() {
+        // Every cut of a chain is a single wire
+        let err = Day25::solve(["a: b", "b: c", "c: d"].into_iter()).unwrap_err();
+        assert!(err.to_string().contains("no cut of exactly three wires"));
     }
 }
@@ -1,36 +1,52 @@
 use std::cmp::Ordering;
 
-use crate::common::Solution;
+use crate::common::{parse_grid, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 use crate::vec2d::{Cell, CellRowRange, Vec2d};
 
 pub enum Day3 {}
 
-impl Solution for Day3 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let matrix: Vec2d<char> = Vec2d::from_lines(lines);
-        get_values(&matrix).sum::<u32>().to_string()
+pub type Day3P2 = Part2<Day3>;
+
+register!(Day3, Day3P2);
+
+impl Puzzle for Day3 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+
+    type Input = Vec2d<char>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_grid(lines, "parse schematic", Ok)
     }
-}
 
-pub enum Day3P2 {}
-impl Solution for Day3P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let matrix: Vec2d<char> = Vec2d::from_lines(lines);
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(get_values(input)?.into_iter().sum::<u32>().into())
+    }
 
-        matrix
-            .cells()
-            .filter_map(|cell| get_gear_ratio(&cell))
-            .sum::<u32>()
-            .to_string()
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let mut total = 0;
+        for cell in input.cells() {
+            total += get_gear_ratio(&cell)?.unwrap_or(0);
+        }
+        Ok(total.into())
     }
 }
 
-fn get_values(matrix: &Vec2d<char>) -> impl Iterator<Item = u32> + '_ {
+fn get_values(matrix: &Vec2d<char>) -> Result<Vec<u32>, SolveError> {
     get_numeric_ranges(matrix)
         .into_iter()
         .filter(|range| borders_symbol(range))
-        .map(|range| range.to_string())
-        .map(|s| s.parse::<u32>().unwrap())
+        .map(|range| parse_number(&range))
+        .collect()
+}
+
+fn parse_number(range: &CellRowRange<char>) -> Result<u32, SolveError> {
+    range
+        .to_string()
+        .parse()
+        .map_err(|err| SolveError::new("read part number", err).on_line(range.first().row() + 1))
 }
 
 fn is_symbol(c: &char) -> bool {
@@ -51,13 +67,12 @@ fn borders_symbol(range: &CellRowRange<char>) -> bool {
 fn get_numeric_ranges(matrix: &Vec2d<char>) -> Vec<CellRowRange<'_, char>> {
     let mut ranges = Vec::new();
 
-    for (row, row_vec) in matrix.inner.iter().enumerate() {
+    for (row, row_vec) in matrix.rows().enumerate() {
         let mut start: Option<usize> = None;
         for (col, val) in row_vec.iter().enumerate() {
             if val.is_ascii_digit() {
-                match start {
-                    None => start = Some(col),
-                    Some(_) => {}
+                if start.is_none() {
+                    start = Some(col)
                 }
             } else {
                 match start {
@@ -77,9 +92,9 @@ fn get_numeric_ranges(matrix: &Vec2d<char>) -> Vec<CellRowRange<'_, char>> {
     ranges
 }
 
-fn get_gear_ratio(cell: &Cell<char>) -> Option<u32> {
+fn get_gear_ratio(cell: &Cell<char>) -> Result<Option<u64>, SolveError> {
     if *cell.value() != '*' {
-        return None;
+        return Ok(None);
     }
 
     let neighbors: Vec<_> = cell.neighbors().collect();
@@ -97,32 +112,18 @@ fn get_gear_ratio(cell: &Cell<char>) -> Option<u32> {
     neighboring_numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
     neighboring_numbers.dedup();
     match neighboring_numbers.as_slice() {
-        [first, second] => Some(
-            first.to_string().parse::<u32>().unwrap() * second.to_string().parse::<u32>().unwrap(),
-        ),
-        _ => None,
+        [first, second] => Ok(Some(
+            u64::from(parse_number(first)?) * u64::from(parse_number(second)?),
+        )),
+        _ => Ok(None),
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::common::Solution;
     use crate::day3::*;
 
-    #[test]
-    fn test_example() {
-        let input = r#"467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598.."#;
-        assert_eq!(Day3::solve(input.lines()), "4361")
-    }
-
     #[test]
     fn test_get_values() {
         let input = r#"467..114..
@@ -135,23 +136,16 @@ mod test {
 ......755.
 ...$.*....
 .664.598.."#;
-        let matrix: Vec2d<char> = Vec2d::from_lines(input.lines());
-        let values: Vec<u32> = get_values(&matrix).collect();
+        let matrix: Vec2d<char> = Vec2d::from_lines(input.lines()).unwrap();
+        let values = get_values(&matrix).unwrap();
         assert_eq!(values, vec![467, 35, 633, 617, 592, 755, 664, 598]);
     }
 
     #[test]
-    fn test_part2_example() {
-        let input = r#"467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598.."#;
-        assert_eq!(Day3P2::solve(input.lines()), "467835");
+    fn test_number_too_large() {
+        let input = ["...........", ".#.........", "99999999999"];
+        let err = Day3::solve(input.into_iter()).unwrap_err();
+        assert_eq!(err.step(), "read part number");
+        assert_eq!(err.line(), Some(3));
     }
 }
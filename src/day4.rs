@@ -1,28 +1,36 @@
 use std::collections::HashSet;
 use std::str::FromStr;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day4 {}
 
-impl Solution for Day4 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|s| s.as_ref().parse::<Ticket>().unwrap())
+pub type Day4P2 = Part2<Day4>;
+
+register!(Day4, Day4P2);
+
+impl Puzzle for Day4 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Scratchcards";
+
+    type Input = Vec<Ticket>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_lines(lines, "parse ticket", str::parse)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
             .map(|ticket| ticket.score())
             .sum::<u64>()
-            .to_string()
+            .into())
     }
-}
 
-pub enum Day4P2 {}
-
-impl Solution for Day4P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let scores: Vec<_> = lines
-            .map(|s| s.as_ref().parse::<Ticket>().unwrap())
-            .map(|ticket| ticket.winning_count())
-            .collect();
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let scores: Vec<_> = input.iter().map(|ticket| ticket.winning_count()).collect();
 
         let mut copies = vec![1u64; scores.len()];
         for (i, score) in scores.iter().enumerate() {
@@ -34,12 +42,12 @@ impl Solution for Day4P2 {
             }
         }
 
-        copies.iter().sum::<u64>().to_string()
+        Ok(copies.iter().sum::<u64>().into())
     }
 }
 
 #[derive(Default)]
-struct Ticket {
+pub struct Ticket {
     #[allow(dead_code)]
     index: u32,
     own_numbers: HashSet<u8>,
@@ -82,13 +90,13 @@ impl FromStr for Ticket {
         let own_numbers: HashSet<u8> = own
             .trim()
             .split_ascii_whitespace()
-            .map(|val| val.parse().unwrap())
-            .collect();
+            .map(|val| val.parse())
+            .collect::<Result<_, _>>()?;
         let winning_numbers: HashSet<u8> = winning
             .trim()
             .split_ascii_whitespace()
-            .map(|val| val.parse().unwrap())
-            .collect();
+            .map(|val| val.parse())
+            .collect::<Result<_, _>>()?;
 
         Ok(Ticket {
             index,
@@ -97,33 +105,3 @@ impl FromStr for Ticket {
         })
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day4::{Day4, Day4P2};
-
-    #[test]
-    fn test_example() {
-        let input = r#"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
-
-        assert_eq!(Day4::solve(input.lines()), "13")
-    }
-
-    #[test]
-    fn test_p2_example() {
-        let input = r#"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
-
-        assert_eq!(Day4P2::solve(input.lines()), "30");
-    }
-}
@@ -1,72 +1,77 @@
-use crate::common::Solution;
+use crate::common::{parse_lines, split_blocks, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day5 {}
 
-impl Solution for Day5 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
+pub type Day5P2 = Part2<Day5>;
+
+register!(Day5, Day5P2);
+
+impl Puzzle for Day5 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    type Input = Problem;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
         let lines: Vec<_> = lines.map(|line| line.as_ref().to_string()).collect();
-        let problem = Problem::from_lines(lines.as_slice());
-        problem
+        Problem::from_lines(lines.as_slice())
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        input
             .seeds
             .iter()
-            .map(|seed| problem.get_location(*seed))
+            .map(|seed| input.get_location(*seed))
             .min()
-            .unwrap()
-            .to_string()
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::msg("find lowest location", "no seeds in input"))
     }
-}
-
-pub enum Day5P2 {}
-
-impl Solution for Day5P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let lines: Vec<_> = lines.map(|line| line.as_ref().to_string()).collect();
-        let problem = Problem::from_lines(lines.as_slice());
 
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
         // this is dumb but it's my bedtime.
         // I think we can do something where we find the "endpoints" of all the eventually mapped
         // ranges, but will save that for another time.
-        let seed_line = &lines[0];
-        let parts: Vec<_> = seed_line
-            .strip_prefix("seeds: ")
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect();
-
-        parts
-            .as_slice()
+        if input.seeds.len() % 2 != 0 {
+            return Err(SolveError::msg(
+                "read seed ranges",
+                "expected an even number of seed values",
+            )
+            .on_line(1));
+        }
+        input
+            .seeds
             .chunks(2)
-            .flat_map(|chunk| (chunk[0]..=(chunk[0] + chunk[1])))
-            .map(|seed| problem.get_location(seed))
+            .flat_map(|chunk| chunk[0]..=(chunk[0] + chunk[1]))
+            .map(|seed| input.get_location(seed))
             .min()
-            .unwrap()
-            .to_string()
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::msg("find lowest location", "no seed ranges in input"))
     }
 }
 
 #[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
-struct Problem {
+pub struct Problem {
     seeds: Vec<u64>,
     mappings: Vec<Mapping>,
 }
 impl Problem {
-    pub fn from_lines(lines: &[String]) -> Problem {
-        let seeds_line = lines[0].as_str();
-        let seeds: Vec<u64> = seeds_line
-            .strip_prefix("seeds: ")
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect();
-
-        let mapping_lines = &lines[2..lines.len()];
-        let mappings = mapping_lines
-            .split(|s| s.is_empty())
-            .map(Mapping::from_lines)
-            .collect();
-
-        Problem { seeds, mappings }
+    pub fn from_lines(lines: &[String]) -> Result<Problem, SolveError> {
+        let mut blocks = split_blocks(lines.iter()).into_iter();
+        let (_, seeds_block) = blocks
+            .next()
+            .ok_or_else(|| SolveError::msg("parse seeds", "input is empty"))?;
+        let seeds: Vec<u64> = parse_seeds(seeds_block[0].as_str())
+            .map_err(|err| SolveError::new("parse seeds", err).on_line(1))?;
+
+        let mappings = blocks
+            .map(|(offset, block)| {
+                Mapping::from_lines(block.as_slice()).map_err(|err| err.offset_line(offset))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Problem { seeds, mappings })
     }
 
     fn get_location(&self, seed: u64) -> u64 {
@@ -90,33 +95,43 @@ impl Problem {
     }
 }
 
+fn parse_seeds(line: &str) -> anyhow::Result<Vec<u64>> {
+    line.strip_prefix("seeds: ")
+        .ok_or_else(|| anyhow::Error::msg("Invalid seeds line").context(line.to_string()))?
+        .split_ascii_whitespace()
+        .map(|s| Ok(s.parse::<u64>()?))
+        .collect()
+}
+
 #[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
-struct Mapping {
+pub struct Mapping {
     source: String,
     destination: String,
     ranges: Vec<MappingRange>,
 }
 
 impl Mapping {
-    pub fn from_lines(lines: &[String]) -> Mapping {
-        let parts = lines[0]
+    /// Parse a mapping block. Line numbers in errors are relative to the start of the block.
+    pub fn from_lines(lines: &[String]) -> Result<Mapping, SolveError> {
+        let (source, destination) = lines[0]
             .strip_suffix(" map:")
-            .unwrap()
-            .split("-to-")
-            .collect::<Vec<_>>();
-        let [source, destination] = parts.as_slice() else {
-            panic!()
-        };
-        let mapping_ranges = lines
-            .iter()
-            .skip(1)
-            .map(|s| MappingRange::from_string(s.as_str()))
-            .collect();
-        Mapping {
+            .and_then(|header| header.split_once("-to-"))
+            .ok_or_else(|| {
+                SolveError::new(
+                    "parse mapping header",
+                    anyhow::Error::msg("Invalid mapping header").context(lines[0].clone()),
+                )
+                .on_line(1)
+            })?;
+        let mapping_ranges = parse_lines(lines.iter().skip(1), "parse mapping range", |s| {
+            MappingRange::from_string(s)
+        })
+        .map_err(|err| err.offset_line(1))?;
+        Ok(Mapping {
             source: source.to_string(),
             destination: destination.to_string(),
             ranges: mapping_ranges,
-        }
+        })
     }
 
     pub fn map_value(&self, value: u64) -> u64 {
@@ -128,23 +143,23 @@ impl Mapping {
 }
 
 #[derive(Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
-struct MappingRange {
+pub struct MappingRange {
     source_start: u64,
     dest_start: u64,
     length: u64,
 }
 
 impl MappingRange {
-    pub fn from_string(line: &str) -> MappingRange {
+    pub fn from_string(line: &str) -> anyhow::Result<MappingRange> {
         let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
         let [dest_start, source_start, length] = parts.as_slice() else {
-            panic!()
+            return Err(anyhow::Error::msg("Invalid mapping range").context(line.to_string()));
         };
-        MappingRange {
-            source_start: source_start.parse().unwrap(),
-            dest_start: dest_start.parse().unwrap(),
-            length: length.parse().unwrap(),
-        }
+        Ok(MappingRange {
+            source_start: source_start.parse()?,
+            dest_start: dest_start.parse()?,
+            length: length.parse()?,
+        })
     }
 
     pub fn get_mapped_value(&self, input: u64) -> Option<u64> {
@@ -161,62 +176,13 @@ impl MappingRange {
 
 #[cfg(test)]
 mod test {
-    use crate::common::Solution;
-    use crate::day5::{Day5, Day5P2, Problem};
-
-    const EXAMPLE_INPUT: &str = r#"seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
 
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4"#;
-
-    #[test]
-    fn test_example() {
-        assert_eq!(Day5::solve(EXAMPLE_INPUT.lines()), "35");
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day5P2::solve(EXAMPLE_INPUT.lines()), "46");
-    }
+    use crate::day5::Problem;
+    use crate::example::example_input;
 
     #[test]
     fn test_parsing() {
-        let problem = Problem::from_lines(
-            EXAMPLE_INPUT
-                .lines()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .as_slice(),
-        );
+        let problem = Problem::from_lines(&example_input!("day5/example.txt")).unwrap();
 
         assert_eq!(problem.seeds, vec![79, 14, 55, 13]);
 
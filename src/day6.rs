@@ -1,62 +1,82 @@
 use std::iter::zip;
 
-use crate::common::Solution;
+use crate::common::{Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day6 {}
 
-impl Solution for Day6 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let lines: Vec<_> = lines.collect();
-        let times = lines[0]
-            .as_ref()
-            .strip_prefix("Time:")
-            .unwrap()
-            .trim()
-            .split_ascii_whitespace()
-            .map(|s| s.parse::<u64>().unwrap());
-
-        let distances = lines[1]
-            .as_ref()
-            .strip_prefix("Distance:")
-            .unwrap()
-            .trim()
-            .split_ascii_whitespace()
-            .map(|s| s.parse::<u64>().unwrap());
-
-        zip(times, distances)
+pub type Day6P2 = Part2<Day6>;
+
+register!(Day6, Day6P2);
+
+impl Puzzle for Day6 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Wait For It";
+
+    /// The time and distance lines, which are read differently by each part
+    type Input = (String, String);
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        get_lines(lines)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let (time_line, distance_line) = input;
+        let times = parse_values(time_line.as_str(), "Time:")
+            .map_err(|err| SolveError::new("parse times", err).on_line(1))?;
+        let distances = parse_values(distance_line.as_str(), "Distance:")
+            .map_err(|err| SolveError::new("parse distances", err).on_line(2))?;
+
+        Ok(zip(times, distances)
             .map(|(time, distance)| Race { time, distance })
             .map(|race| race.get_record_setting_ways())
             .product::<u64>()
-            .to_string()
+            .into())
     }
-}
 
-pub enum Day6P2 {}
-
-impl Solution for Day6P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let lines: Vec<_> = lines.collect();
-        let time = lines[0]
-            .as_ref()
-            .strip_prefix("Time:")
-            .unwrap()
-            .replace(' ', "")
-            .parse::<u64>()
-            .unwrap();
-
-        let distance = lines[1]
-            .as_ref()
-            .strip_prefix("Distance:")
-            .unwrap()
-            .replace(' ', "")
-            .parse::<u64>()
-            .unwrap();
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let (time_line, distance_line) = input;
+        let time = parse_kerned_value(time_line.as_str(), "Time:")
+            .map_err(|err| SolveError::new("parse time", err).on_line(1))?;
+        let distance = parse_kerned_value(distance_line.as_str(), "Distance:")
+            .map_err(|err| SolveError::new("parse distance", err).on_line(2))?;
 
         let race = Race { time, distance };
-        race.get_record_setting_ways().to_string()
+        Ok(race.get_record_setting_ways().into())
     }
 }
 
+fn get_lines(
+    mut lines: impl Iterator<Item = impl AsRef<str>>,
+) -> Result<(String, String), SolveError> {
+    let mut next_line = |line: usize| {
+        lines
+            .next()
+            .map(|s| s.as_ref().to_string())
+            .ok_or_else(|| SolveError::msg("read races", "input ended early").on_line(line))
+    };
+    Ok((next_line(1)?, next_line(2)?))
+}
+
+fn parse_values(line: &str, prefix: &str) -> anyhow::Result<Vec<u64>> {
+    line.strip_prefix(prefix)
+        .ok_or_else(|| anyhow::Error::msg("Missing line prefix").context(line.to_string()))?
+        .trim()
+        .split_ascii_whitespace()
+        .map(|s| Ok(s.parse::<u64>()?))
+        .collect()
+}
+
+/// Parse the line as a single number, ignoring the spaces between the digits
+fn parse_kerned_value(line: &str, prefix: &str) -> anyhow::Result<u64> {
+    Ok(line
+        .strip_prefix(prefix)
+        .ok_or_else(|| anyhow::Error::msg("Missing line prefix").context(line.to_string()))?
+        .replace(' ', "")
+        .parse::<u64>()?)
+}
+
 struct Race {
     time: u64,
     distance: u64,
@@ -67,11 +87,7 @@ impl Race {
         // we win if (time - acc) * acc > distance
         // this yields a quadratic equation that we can use
         // - acc^2 + time * acc - distance > 0
-        let (first, second) = solve_quadratic(
-            -1f64,
-            1f64 * self.time as f64,
-            -1f64 * (self.distance as f64),
-        );
+        let (first, second) = solve_quadratic(-1f64, self.time as f64, -(self.distance as f64));
 
         // there's probably a more principled way to do this, but this basically just gets rid of
         // any weird rounding errors that we may have to make sure the ones we chosen are winnable
@@ -101,22 +117,3 @@ fn solve_quadratic(a: f64, b: f64, c: f64) -> (f64, f64) {
         (-b - f64::sqrt(b.powi(2) - (4f64 * a * c))) / (2f64 * a),
     )
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day6::{Day6, Day6P2};
-
-    const INPUT: &str = r#"Time:      7  15   30
-Distance:  9  40  200"#;
-
-    #[test]
-    fn test_example() {
-        assert_eq!(Day6::solve(INPUT.lines()), "288");
-    }
-
-    #[test]
-    fn test_part2_example() {
-        assert_eq!(Day6P2::solve(INPUT.lines()), "71503");
-    }
-}
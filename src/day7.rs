@@ -3,39 +3,50 @@ use std::collections::HashMap;
 use std::iter::zip;
 use std::str::FromStr;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day7 {}
 
-impl Solution for Day7 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut hands: Vec<Hand> = lines.map(|line| line.as_ref().parse().unwrap()).collect();
+pub type Day7P2 = Part2<Day7>;
+
+register!(Day7, Day7P2);
+
+impl Puzzle for Day7 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Camel Cards";
+
+    type Input = Vec<Hand>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_lines(lines, "parse hand", str::parse)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let mut hands: Vec<&Hand> = input.iter().collect();
         let count = hands.len();
         hands.sort();
 
-        hands
+        Ok(hands
             .iter()
             .enumerate()
             .map(|(index, hand)| ((count - index) as u64) * hand.bid)
             .sum::<u64>()
-            .to_string()
+            .into())
     }
-}
 
-pub enum Day7P2 {}
-
-impl Solution for Day7P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let mut hands: Vec<Hand> = lines.map(|line| line.as_ref().parse().unwrap()).collect();
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        let mut hands: Vec<&Hand> = input.iter().collect();
         let count = hands.len();
-        hands.sort_by(compare_hands_2);
+        hands.sort_by(|first, second| compare_hands_2(first, second));
 
-        hands
+        Ok(hands
             .iter()
             .enumerate()
             .map(|(index, hand)| ((count - index) as u64) * hand.bid)
             .sum::<u64>()
-            .to_string()
+            .into())
     }
 }
 
@@ -47,7 +58,7 @@ static CARD_ORDERING_2: &[char] = &[
     'A', 'K', 'Q', 'T', '9', '8', '7', '6', '5', '4', '3', '2', 'J',
 ];
 
-struct Hand {
+pub struct Hand {
     original: String,
     ordered_card_counts: Vec<(char, usize)>,
     bid: u64,
@@ -61,6 +72,11 @@ impl FromStr for Hand {
         let [cards, bid] = parts.as_slice() else {
             return Err(anyhow::Error::msg("Invalid input line").context(line.to_string()));
         };
+        if let Some(card) = cards.chars().find(|card| !CARD_ORDERING.contains(card)) {
+            return Err(
+                anyhow::Error::msg(format!("Invalid card: {}", card)).context(line.to_string())
+            );
+        }
 
         Ok(Hand {
             original: cards.to_string(),
@@ -199,24 +215,3 @@ fn compare_card_2(a: char, b: char) -> Ordering {
 
     a_index.cmp(&b_index)
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day7::{Day7, Day7P2};
-
-    const INPUT: &str = r#"32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483"#;
-    #[test]
-    fn test_example() {
-        assert_eq!(Day7::solve(INPUT.lines()), "6440")
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day7P2::solve(INPUT.lines()), "5905")
-    }
-}
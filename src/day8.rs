@@ -1,66 +1,97 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day8 {}
 
-impl Solution for Day8 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let network = Network::from_lines(lines);
-        network.count_steps_to_end().to_string()
+pub type Day8P2 = Part2<Day8>;
+
+register!(Day8, Day8P2);
+
+impl Puzzle for Day8 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    type Input = Network;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        Network::from_lines(lines)
     }
-}
 
-pub enum Day8P2 {}
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        input
+            .count_steps_to_end()
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::msg("find start node", "no node named AAA"))
+    }
 
-impl Solution for Day8P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        let network = Network::from_lines(lines);
-        network
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        input
             .nodes
             .values()
             .filter(|node| node.ends_with_a())
-            .map(|node| network.count_steps_to_end_2(node))
+            .map(|node| input.count_steps_to_end_2(node))
             .reduce(lcm)
-            .unwrap()
-            .to_string()
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::msg("find start nodes", "no node ends with A"))
     }
 }
 
-struct Network {
+pub struct Network {
     directions: Vec<Direction>,
     nodes: HashMap<String, Node>,
 }
 
 impl Network {
-    pub fn from_lines(mut lines: impl Iterator<Item = impl AsRef<str>>) -> Network {
+    pub fn from_lines(
+        mut lines: impl Iterator<Item = impl AsRef<str>>,
+    ) -> Result<Network, SolveError> {
         let mut nodes: HashMap<String, Node> = HashMap::new();
-        let first_line = lines.next().unwrap();
-        let first_line = first_line.as_ref();
-        let directions: Vec<Direction> =
-            first_line.chars().map(|c| c.try_into().unwrap()).collect();
+        let first_line = lines
+            .next()
+            .ok_or_else(|| SolveError::msg("parse directions", "input is empty"))?;
+        let directions: Vec<Direction> = first_line
+            .as_ref()
+            .chars()
+            .map(|c| c.try_into())
+            .collect::<anyhow::Result<_>>()
+            .map_err(|err| SolveError::new("parse directions", err).on_line(1))?;
         let _ = lines.next();
 
-        for line in lines {
-            let node: Node = line.as_ref().parse().unwrap();
+        let parsed = parse_lines(lines, "parse node", str::parse::<Node>)
+            .map_err(|err| err.offset_line(2))?;
+        for node in parsed {
             nodes.insert(node.name.clone(), node);
         }
 
-        Network { directions, nodes }
+        for node in nodes.values() {
+            for next in [&node.left, &node.right] {
+                if !nodes.contains_key(next) {
+                    return Err(SolveError::msg(
+                        "link nodes",
+                        format!("node {} refers to unknown node {}", node.name, next),
+                    ));
+                }
+            }
+        }
+
+        Ok(Network { directions, nodes })
     }
 
-    pub fn count_steps_to_end(&self) -> usize {
-        let mut cur_node = self.nodes.get("AAA").unwrap();
+    pub fn count_steps_to_end(&self) -> Option<usize> {
+        let mut cur_node = self.nodes.get("AAA")?;
         let mut steps: usize = 0;
         for direction in self.directions.iter().cycle() {
             if cur_node.is_end() {
-                return steps;
+                return Some(steps);
             }
             steps += 1;
             cur_node = self.get_next(cur_node, direction)
         }
-        steps
+        Some(steps)
     }
 
     pub fn count_steps_to_end_2(&self, node: &Node) -> usize {
@@ -84,7 +115,7 @@ impl Network {
     }
 }
 
-struct Node {
+pub struct Node {
     name: String,
     left: String,
     right: String,
@@ -94,15 +125,17 @@ impl FromStr for Node {
     type Err = anyhow::Error;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let (name, directions) = line.split_once(" = ").unwrap();
+        let (name, directions) = line
+            .split_once(" = ")
+            .ok_or_else(|| anyhow::Error::msg("Invalid node").context(line.to_string()))?;
         let (left, right) = directions
             .trim()
             .strip_prefix('(')
-            .unwrap()
-            .strip_suffix(')')
-            .unwrap()
-            .split_once(", ")
-            .unwrap();
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(", "))
+            .ok_or_else(|| {
+                anyhow::Error::msg("Invalid node destinations").context(directions.to_string())
+            })?;
         Ok(Node {
             name: name.to_string(),
             left: left.to_string(),
@@ -125,7 +158,7 @@ impl Node {
     }
 }
 
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
@@ -152,45 +185,3 @@ impl TryFrom<char> for Direction {
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day8::{Day8, Day8P2};
-
-    const INPUT1: &str = r#"RL
-
-AAA = (BBB, CCC)
-BBB = (DDD, EEE)
-CCC = (ZZZ, GGG)
-DDD = (DDD, DDD)
-EEE = (EEE, EEE)
-GGG = (GGG, GGG)
-ZZZ = (ZZZ, ZZZ)"#;
-
-    const INPUT2: &str = r#"LLR
-
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)"#;
-    #[test]
-    fn test_example_cases() {
-        assert_eq!(Day8::solve(INPUT1.lines()), "2");
-        assert_eq!(Day8::solve(INPUT2.lines()), "6");
-    }
-
-    #[test]
-    fn test_example_part2() {
-        let input = r#"LR
-
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)"#;
-        assert_eq!(Day8P2::solve(input.lines()), "6");
-    }
-}
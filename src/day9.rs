@@ -1,30 +1,43 @@
 use std::str::FromStr;
 
-use crate::common::Solution;
+use crate::common::{parse_lines, Answer, Part2, Puzzle, SolveError};
+use crate::params::Params;
+use crate::registry::register;
 
 pub enum Day9 {}
 
-impl Solution for Day9 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|line| Sequence::<i64>::from_str(line.as_ref()).unwrap().get_next())
-            .sum::<i64>()
-            .to_string()
+pub type Day9P2 = Part2<Day9>;
+
+register!(Day9, Day9P2);
+
+impl Puzzle for Day9 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    type Input = Vec<Sequence<i64>>;
+
+    fn parse(lines: impl Iterator<Item = impl AsRef<str>>) -> Result<Self::Input, SolveError> {
+        parse_lines(lines, "parse sequence", Sequence::<i64>::from_str)
     }
-}
 
-pub enum Day9P2 {}
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
+            .map(|sequence| sequence.get_next())
+            .sum::<i64>()
+            .into())
+    }
 
-impl Solution for Day9P2 {
-    fn solve(lines: impl Iterator<Item = impl AsRef<str>>) -> String {
-        lines
-            .map(|line| Sequence::<i64>::from_str(line.as_ref()).unwrap().get_prev())
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
+            .map(|sequence| sequence.get_prev())
             .sum::<i64>()
-            .to_string()
+            .into())
     }
 }
 
-struct Sequence<T> {
+pub struct Sequence<T> {
     values: Vec<T>,
 }
 
@@ -86,22 +99,3 @@ impl<T> From<Vec<T>> for Sequence<T> {
         Sequence { values }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use crate::common::Solution;
-    use crate::day9::{Day9, Day9P2};
-
-    const EXAMPLE_INPUT: &str = r#"0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45"#;
-    #[test]
-    fn test_example() {
-        assert_eq!(Day9::solve(EXAMPLE_INPUT.lines()), "114");
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(Day9P2::solve(EXAMPLE_INPUT.lines()), "2");
-    }
-}
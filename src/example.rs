@@ -0,0 +1,142 @@
+use std::str::FromStr;
+
+use crate::common::Answer;
+
+/// The line separating the header of an example file from its input
+pub const SEPARATOR: &str = "---";
+
+/// An example input together with the answers it should produce, as read from an example file.
+///
+/// The file starts with a header of `part <n>: <answer>` and `param <name>=<value>` lines, where
+/// blank lines and lines starting with `#` are skipped. A `---` line ends the header, and the
+/// rest of the file is the puzzle input:
+///
+/// ```text
+/// # The example from the puzzle description, with fewer steps
+/// part 1: 16
+/// param steps=6
+/// ---
+/// ...........
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub answers: Vec<(u8, String)>,
+    pub params: Vec<String>,
+    pub input: Vec<String>,
+}
+
+impl Example {
+    /// The expected answer for `part`, if the example has one
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|(answer_part, _)| *answer_part == part)
+            .map(|(_, answer)| answer.as_str())
+    }
+
+    pub fn matches(&self, part: u8, answer: &Answer) -> bool {
+        self.answer(part).is_some_and(|expected| answer == expected)
+    }
+}
+
+impl FromStr for Example {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let mut answers = Vec::new();
+        let mut params = Vec::new();
+        loop {
+            let Some(line) = lines.next() else {
+                return Err(anyhow::Error::msg(format!(
+                    "expected a `{}` line before the input",
+                    SEPARATOR
+                )));
+            };
+            let line = line.trim();
+            if line == SEPARATOR {
+                break;
+            } else if line.is_empty() || line.starts_with('#') {
+                continue;
+            } else if let Some(param) = line.strip_prefix("param ") {
+                params.push(param.trim().to_string());
+            } else if let Some((part, answer)) = line
+                .strip_prefix("part ")
+                .and_then(|rest| rest.split_once(':'))
+            {
+                let part = part
+                    .trim()
+                    .parse()
+                    .map_err(|err| anyhow::Error::new(err).context(line.to_string()))?;
+                answers.push((part, answer.trim().to_string()));
+            } else {
+                return Err(anyhow::Error::msg(
+                    "expected `part <n>: <answer>`, `param <name>=<value>` or `---`",
+                )
+                .context(line.to_string()));
+            }
+        }
+        if answers.is_empty() {
+            return Err(anyhow::Error::msg(
+                "the example has no `part <n>: <answer>` lines",
+            ));
+        }
+        Ok(Example {
+            answers,
+            params,
+            input: lines.map(str::to_string).collect(),
+        })
+    }
+}
+
+/// The input lines of an example file, given by its path under `tests/examples`, for unit tests
+/// of the helpers behind a solution
+#[cfg(test)]
+macro_rules! example_input {
+    ($path:literal) => {
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/examples/",
+            $path
+        ))
+        .parse::<$crate::example::Example>()
+        .unwrap()
+        .input
+    };
+}
+
+#[cfg(test)]
+pub(crate) use example_input;
+
+#[cfg(test)]
+mod test {
+    use crate::common::Answer;
+    use crate::example::Example;
+
+    #[test]
+    fn test_parse_example() {
+        let example: Example =
+            "# comment\npart 1: 16\n\nparam steps=6\npart 2: some text\n---\n#..\n.#.\n"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            example,
+            Example {
+                answers: vec![(1, "16".to_string()), (2, "some text".to_string())],
+                params: vec!["steps=6".to_string()],
+                input: vec!["#..".to_string(), ".#.".to_string()],
+            }
+        );
+        assert!(example.matches(1, &Answer::Int(16)));
+        assert!(!example.matches(1, &Answer::NotComputed));
+        assert_eq!(example.answer(3), None);
+    }
+
+    #[test]
+    fn test_parse_example_errors() {
+        assert!("part 1: 2\n1 2 3".parse::<Example>().is_err());
+        assert!("---\n1 2 3".parse::<Example>().is_err());
+        let err = "part one: 2\n---\n".parse::<Example>().unwrap_err();
+        assert!(format!("{:#}", err).starts_with("part one: 2: "));
+    }
+}
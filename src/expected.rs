@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use crate::common::{parse_lines, Answer, SolveError};
+
+/// The known answer for one part of a day's puzzle, as read from an expected-answers file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExpectedAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+}
+
+impl ExpectedAnswer {
+    pub fn matches(&self, answer: &Answer) -> bool {
+        answer == self.answer.as_str()
+    }
+}
+
+impl FromStr for ExpectedAnswer {
+    type Err = anyhow::Error;
+
+    /// Parse a line of the form `<day> <part> <answer>`. The answer is the rest of the line, so
+    /// it may contain spaces.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.trim().splitn(3, char::is_whitespace);
+        let (Some(day), Some(part), Some(answer)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(
+                anyhow::Error::msg("expected `<day> <part> <answer>`").context(s.to_string())
+            );
+        };
+        Ok(ExpectedAnswer {
+            day: day.parse()?,
+            part: part.parse()?,
+            answer: answer.trim().to_string(),
+        })
+    }
+}
+
+/// Parse an expected-answers file, skipping blank lines and lines starting with `#`
+pub fn parse_expected_answers(
+    lines: impl Iterator<Item = impl AsRef<str>>,
+) -> Result<Vec<ExpectedAnswer>, SolveError> {
+    Ok(parse_lines(lines, "parse expected answer", |line| {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            Ok(None)
+        } else {
+            line.parse().map(Some)
+        }
+    })?
+    .into_iter()
+    .flatten()
+    .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::Answer;
+    use crate::expected::{parse_expected_answers, ExpectedAnswer};
+
+    #[test]
+    fn test_parse_expected_answers() {
+        let answers =
+            parse_expected_answers(["# day part answer", "1 1 142", "", "25 1 a b"].iter())
+                .unwrap();
+        assert_eq!(
+            answers,
+            vec![
+                ExpectedAnswer {
+                    day: 1,
+                    part: 1,
+                    answer: "142".to_string()
+                },
+                ExpectedAnswer {
+                    day: 25,
+                    part: 1,
+                    answer: "a b".to_string()
+                }
+            ]
+        );
+        assert!(answers[0].matches(&Answer::from(142u64)));
+        assert!(!answers[0].matches(&Answer::NotComputed));
+
+        let err = parse_expected_answers(["1 1 142", "1 x 5"].iter()).unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert!(parse_expected_answers(["1 1"].iter()).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::render::Color;
+use crate::vec2d::Vec2d;
+
+/// A pixel colour as red, green and blue intensities
+pub type Rgb = [u8; 3];
+
+impl From<Color> for Rgb {
+    fn from(color: Color) -> Rgb {
+        match color {
+            Color::Red => [255, 0, 0],
+            Color::Green => [0, 255, 0],
+            Color::Yellow => [255, 255, 0],
+            Color::Blue => [0, 0, 255],
+            Color::Magenta => [255, 0, 255],
+            Color::Cyan => [0, 255, 255],
+        }
+    }
+}
+
+impl<T> Vec2d<T> {
+    /// Write the grid as a binary PPM image, where every cell is a `scale` by `scale` square of
+    /// the colour that `color` gives its value
+    pub fn write_ppm(
+        &self,
+        out: &mut impl Write,
+        scale: usize,
+        color: impl Fn(&T) -> Rgb,
+    ) -> io::Result<()> {
+        self.write_netpbm(out, "P6", scale, color)
+    }
+
+    /// Write the grid as a binary PGM image, where every cell is a `scale` by `scale` square of
+    /// the grey level that `gray` gives its value, from 0 for black to 255 for white
+    pub fn write_pgm(
+        &self,
+        out: &mut impl Write,
+        scale: usize,
+        gray: impl Fn(&T) -> u8,
+    ) -> io::Result<()> {
+        self.write_netpbm(out, "P5", scale, |value| [gray(value)])
+    }
+
+    /// Save the grid as a binary PPM image at `path`, as with [`Vec2d::write_ppm`]
+    pub fn save_ppm(
+        &self,
+        path: impl AsRef<Path>,
+        scale: usize,
+        color: impl Fn(&T) -> Rgb,
+    ) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut out, scale, color)?;
+        out.flush()
+    }
+
+    /// Save the grid as a binary PGM image at `path`, as with [`Vec2d::write_pgm`]
+    pub fn save_pgm(
+        &self,
+        path: impl AsRef<Path>,
+        scale: usize,
+        gray: impl Fn(&T) -> u8,
+    ) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_pgm(&mut out, scale, gray)?;
+        out.flush()
+    }
+
+    fn write_netpbm<P: AsRef<[u8]>>(
+        &self,
+        out: &mut impl Write,
+        magic: &str,
+        scale: usize,
+        pixel: impl Fn(&T) -> P,
+    ) -> io::Result<()> {
+        if scale == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the scale must be at least 1",
+            ));
+        }
+        let width = self.num_cols() * scale;
+        let height = self.num_rows() * scale;
+        write!(out, "{}\n{} {}\n255\n", magic, width, height)?;
+        let mut line = Vec::new();
+        for row in self.rows() {
+            line.clear();
+            for value in row {
+                let pixel = pixel(value);
+                for _ in 0..scale {
+                    line.extend_from_slice(pixel.as_ref());
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::image::Rgb;
+    use crate::render::Color;
+    use crate::vec2d::Vec2d;
+
+    #[test]
+    fn test_write_ppm() {
+        let grid = Vec2d::from_lines(["#.", ".."].into_iter()).unwrap();
+        let mut out = Vec::new();
+        grid.write_ppm(&mut out, 1, |&c| {
+            if c == '#' {
+                Rgb::from(Color::Red)
+            } else {
+                [0, 0, 0]
+            }
+        })
+        .unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_write_pgm_scaled() {
+        let grid = Vec2d::from_lines(["#.", "##", ".."].into_iter()).unwrap();
+        let mut out = Vec::new();
+        grid.write_pgm(&mut out, 2, |&c| if c == '#' { 255 } else { 0 })
+            .unwrap();
+        let header = b"P5\n4 6\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 4 * 6);
+        assert_eq!(&pixels[..8], &[255, 255, 0, 0, 255, 255, 0, 0]);
+        assert_eq!(&pixels[8..16], &[255; 8]);
+        assert_eq!(&pixels[16..], &[0; 8]);
+
+        assert!(grid.write_pgm(&mut Vec::new(), 0, |_| 0).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// The environment variable that sets the directory containing the puzzle inputs
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
+
+/// The directory containing the puzzle inputs when none is given
+pub const DEFAULT_INPUTS: &str = "inputs";
+
+/// The path that reads the input from stdin
+pub const STDIN: &str = "-";
+
+/// The paths that may contain the input for `day` in `inputs`, in the order they are tried
+pub fn candidates(inputs: &Path, day: u8) -> Vec<PathBuf> {
+    vec![
+        inputs.join(format!("day{:02}.txt", day)),
+        inputs.join(format!("day{}.txt", day)),
+    ]
+}
+
+/// Find the input for `day` in `inputs`, named either `dayNN.txt` or `dayN.txt`
+pub fn find(inputs: &Path, day: u8) -> Result<PathBuf, MissingInput> {
+    let tried = candidates(inputs, day);
+    match tried.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(MissingInput { day, tried }),
+    }
+}
+
+/// Read the lines of `path`, or of stdin if the path is `-`
+pub fn read_lines(path: &Path) -> anyhow::Result<Vec<String>> {
+    if path == Path::new(STDIN) {
+        return std::io::stdin()
+            .lock()
+            .lines()
+            .collect::<Result<_, _>>()
+            .map_err(|err| anyhow::Error::new(err).context("could not read stdin"));
+    }
+    let file = File::open(path).map_err(|err| {
+        anyhow::Error::new(err).context(format!("could not open {}", path.display()))
+    })?;
+    BufReader::new(file)
+        .lines()
+        .collect::<Result<_, _>>()
+        .map_err(|err| {
+            anyhow::Error::new(err).context(format!("could not read {}", path.display()))
+        })
+}
+
+/// The error returned by [`find`] when there is no input for a day
+#[derive(Debug)]
+pub struct MissingInput {
+    day: u8,
+    tried: Vec<PathBuf>,
+}
+
+impl Display for MissingInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no input for day {}, tried ", self.day)?;
+        for (index, path) in self.tried.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" and ")?;
+            }
+            write!(f, "{}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MissingInput {}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::input::{find, read_lines};
+
+    #[test]
+    fn test_find_reports_tried_paths() {
+        let err = find(Path::new("no/such/dir"), 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "no input for day 3, tried {} and {}",
+                PathBuf::from("no/such/dir").join("day03.txt").display(),
+                PathBuf::from("no/such/dir").join("day3.txt").display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_find_and_read() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day7.txt"), "a\nb\n").unwrap();
+
+        let path = find(&dir, 7).unwrap();
+        assert_eq!(path, dir.join("day7.txt"));
+        assert_eq!(read_lines(&path).unwrap(), vec!["a", "b"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_missing_file_names_path() {
+        let err = read_lines(Path::new("no/such/file.txt")).unwrap_err();
+        assert!(format!("{:#}", err).starts_with("could not open no/such/file.txt: "));
+    }
+}
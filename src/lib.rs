@@ -1,3 +1,5 @@
+pub mod animation;
+pub mod bench;
 pub mod common;
 pub mod day1;
 pub mod day2;
@@ -8,6 +10,10 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod example;
+pub mod expected;
+pub mod image;
+pub mod input;
 
 pub mod day10;
 
@@ -26,4 +32,9 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod params;
+pub mod pathfinding;
+pub mod regions;
+pub mod registry;
+pub mod render;
 pub mod vec2d;
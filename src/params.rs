@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::common::SolveError;
+
+/// A named constant that a puzzle uses while solving, which can be overridden at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    /// The part of the puzzle that uses the parameter
+    pub part: u8,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+/// Overrides for the parameters of a puzzle. Parameters that are not overridden take their
+/// default value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    overrides: HashMap<String, String>,
+}
+
+impl Params {
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.overrides.insert(name.into(), value.into());
+    }
+
+    /// Add an override written as `name=value`
+    pub fn set_from_str(&mut self, assignment: &str) -> anyhow::Result<()> {
+        let (name, value) = assignment.split_once('=').ok_or_else(|| {
+            anyhow::Error::msg("expected a parameter of the form name=value")
+                .context(assignment.to_string())
+        })?;
+        self.set(name.trim(), value.trim());
+        Ok(())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.overrides.keys().map(String::as_str)
+    }
+
+    /// Keep only the overrides whose name satisfies `keep`
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.overrides.retain(|name, _| keep(name));
+    }
+
+    /// The value of `param`, either overridden or the default
+    pub fn get<T>(&self, param: &Param) -> Result<T, SolveError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self
+            .overrides
+            .get(param.name)
+            .map(String::as_str)
+            .unwrap_or(param.default);
+        value.parse().map_err(|err| {
+            SolveError::msg(
+                "read parameter",
+                format!("invalid value {:?} for {}: {}", value, param.name, err),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::params::{Param, Params};
+
+    const STEPS: Param = Param {
+        name: "steps",
+        part: 1,
+        default: "64",
+        description: "Number of steps",
+    };
+
+    #[test]
+    fn test_params() {
+        let mut params = Params::default();
+        assert_eq!(params.get::<usize>(&STEPS).unwrap(), 64);
+
+        params.set_from_str("steps = 10").unwrap();
+        assert_eq!(params.get::<usize>(&STEPS).unwrap(), 10);
+        assert_eq!(params.names().collect::<Vec<_>>(), vec!["steps"]);
+
+        params.set("steps", "many");
+        assert_eq!(
+            params.get::<usize>(&STEPS).unwrap_err().to_string(),
+            "failed to read parameter: invalid value \"many\" for steps: invalid digit found in string"
+        );
+        assert!(params.set_from_str("steps").is_err());
+    }
+}
@@ -0,0 +1,310 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use num::Zero;
+use priority_queue::PriorityQueue;
+
+use crate::vec2d::{RowCol, Vec2d};
+
+/// The states reached by a search, with the cost of the cheapest way found to reach each one
+/// and the state it was reached from
+#[derive(Debug, Clone)]
+pub struct SearchTree<S, C> {
+    start: S,
+    costs: HashMap<S, C>,
+    parents: HashMap<S, S>,
+}
+
+/// A path found by a search, from the start state to the goal inclusive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> SearchTree<S, C> {
+    fn new(start: S, zero: C) -> SearchTree<S, C> {
+        SearchTree {
+            costs: HashMap::from([(start.clone(), zero)]),
+            start,
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn start(&self) -> &S {
+        &self.start
+    }
+
+    /// The cost of reaching `state`, or `None` if it was not reached
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    /// Every reached state with the cost of reaching it, in no particular order
+    pub fn costs(&self) -> impl Iterator<Item = (&S, C)> {
+        self.costs.iter().map(|(state, cost)| (state, *cost))
+    }
+
+    /// The states from the start to `goal` inclusive, or `None` if `goal` was not reached
+    pub fn path_to(&self, goal: &S) -> Option<Path<S, C>> {
+        let cost = self.cost(goal)?;
+        let mut states = vec![goal.clone()];
+        while let Some(parent) = self.parents.get(states.last().unwrap()) {
+            states.push(parent.clone());
+        }
+        states.reverse();
+        Some(Path { states, cost })
+    }
+
+    /// Record a new cheapest way of reaching `state`, returning false if it is not cheaper
+    fn relax(&mut self, state: &S, parent: &S, cost: C) -> bool
+    where
+        C: Ord,
+    {
+        if self.costs.get(state).is_some_and(|&known| known <= cost) {
+            return false;
+        }
+        self.costs.insert(state.clone(), cost);
+        self.parents.insert(state.clone(), parent.clone());
+        true
+    }
+}
+
+/// Breadth-first search from `start`, where every step costs 1. `successors` returns the states
+/// reachable in one step from a state.
+pub fn bfs<S, I>(start: S, successors: impl FnMut(&S) -> I) -> SearchTree<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs_within(start, usize::MAX, successors)
+}
+
+/// Breadth-first search from `start` that takes at most `max_steps` steps, for state spaces that
+/// are too large to search completely, such as an infinitely tiled grid
+pub fn bfs_within<S, I>(
+    start: S,
+    max_steps: usize,
+    mut successors: impl FnMut(&S) -> I,
+) -> SearchTree<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut tree = SearchTree::new(start.clone(), 0);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((state, steps)) = queue.pop_front() {
+        if steps == max_steps {
+            continue;
+        }
+        for next in successors(&state) {
+            if !tree.costs.contains_key(&next) {
+                tree.relax(&next, &state, steps + 1);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    tree
+}
+
+/// Dijkstra's algorithm from `start` over every reachable state. `successors` returns the states
+/// reachable in one step from a state, each with the non-negative cost of the step.
+pub fn dijkstra<S, C, I>(start: S, successors: impl FnMut(&S) -> I) -> SearchTree<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    search(start, successors, |_| C::zero(), |_| false).0
+}
+
+/// Dijkstra's algorithm from `start`, stopping at the first state satisfying `is_goal` and
+/// returning the cheapest path to it
+pub fn dijkstra_to<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::zero(), is_goal)
+}
+
+/// A* search from `start` to the first state satisfying `is_goal`. `heuristic` estimates the cost
+/// from a state to the nearest goal, and the returned path is the cheapest one as long as the
+/// estimate never exceeds the true cost.
+pub fn astar<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (tree, goal) = search(start, successors, heuristic, is_goal);
+    tree.path_to(&goal?)
+}
+
+/// Best-first search ordered by the cost so far plus the heuristic, stopping at the first goal
+fn search<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> (SearchTree<S, C>, Option<S>)
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut tree = SearchTree::new(start.clone(), C::zero());
+    let mut queue = PriorityQueue::new();
+    queue.push(start.clone(), Reverse(heuristic(&start)));
+
+    while let Some((state, _)) = queue.pop() {
+        if is_goal(&state) {
+            return (tree, Some(state));
+        }
+        let cost = tree.costs[&state];
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            if tree.relax(&next, &state, next_cost) {
+                let estimate = next_cost + heuristic(&next);
+                queue.push_increase(next, Reverse(estimate));
+            }
+        }
+    }
+    (tree, None)
+}
+
+/// Breadth-first search over the cells of `grid`, moving up, down, left or right between cells
+/// whose values satisfy `passable`
+pub fn grid_bfs<T>(
+    grid: &Vec2d<T>,
+    start: RowCol,
+    passable: impl Fn(&T) -> bool,
+) -> SearchTree<RowCol, usize> {
+    bfs(start, |coords| {
+        grid.get_cell(coords.row, coords.col)
+            .into_iter()
+            .flat_map(|cell| cell.cardinal_neighbors())
+            .filter(|cell| passable(cell.value()))
+            .map(|cell| cell.coords())
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pathfinding::{astar, bfs, bfs_within, dijkstra, dijkstra_to, grid_bfs, Path};
+    use crate::vec2d::{Pos, RowCol, Vec2d};
+
+    const MAZE: &str = "S.#....
+.##.##.
+...#...
+.#...#E";
+
+    fn maze() -> (Vec2d<char>, RowCol, RowCol) {
+        let grid = Vec2d::from_lines(MAZE.lines()).unwrap();
+        let find = |c| {
+            grid.cells()
+                .find(|cell| *cell.value() == c)
+                .unwrap()
+                .coords()
+        };
+        let (start, end) = (find('S'), find('E'));
+        (grid, start, end)
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let tree = bfs(1u32, |&n| {
+            [n * 2, n + 1].into_iter().filter(|&next| next <= 10)
+        });
+        assert_eq!(tree.cost(&1), Some(0));
+        assert_eq!(tree.cost(&10), Some(4));
+        assert_eq!(tree.path_to(&10).unwrap().states, vec![1, 2, 4, 5, 10]);
+        assert_eq!(tree.cost(&11), None);
+    }
+
+    #[test]
+    fn test_bfs_on_tiled_grid() {
+        let (grid, start, _) = maze();
+        let tiled = grid.tiled();
+        let tree = bfs_within(Pos::from(start), 20, |pos| {
+            tiled
+                .cardinal_neighbors(pos)
+                .filter(|next| *tiled.get(next) != '#')
+                .collect::<Vec<_>>()
+        });
+        assert!(tree.costs().all(|(_, steps)| steps <= 20));
+        // Going up from the start wraps into the copy of the maze above
+        let above = Pos { row: -1, col: 0 };
+        assert_eq!(tree.cost(&above), Some(1));
+        assert_eq!(tiled.tile_index(&above), Pos { row: -1, col: 0 });
+
+        let torus = grid.torus();
+        let tree = bfs(start, |coords| {
+            torus
+                .cardinal_neighbors(coords)
+                .filter(|next| grid.get_row_col(next) != Some(&'#'))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(tree.cost(&RowCol { row: 3, col: 0 }), Some(1));
+    }
+
+    #[test]
+    fn test_grid_bfs() {
+        let (grid, start, end) = maze();
+        let tree = grid_bfs(&grid, start, |&c| c != '#');
+        assert_eq!(tree.cost(&end), Some(11));
+        let path = tree.path_to(&end).unwrap();
+        assert_eq!(path.states.len(), 12);
+        assert!(path
+            .states
+            .iter()
+            .all(|coords| grid.get_row_col(coords) != Some(&'#')));
+        assert_eq!(tree.cost(&RowCol { row: 0, col: 2 }), None);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar_agree() {
+        // Stepping onto a '.' costs 1, and stepping onto an 'x' costs 5
+        let grid = Vec2d::from_lines(["..x..", ".xxx.", "...x.", "x...."].into_iter()).unwrap();
+        let start = RowCol { row: 0, col: 0 };
+        let end = RowCol { row: 3, col: 4 };
+        let successors = |coords: &RowCol| {
+            grid.get_cell(coords.row, coords.col)
+                .unwrap()
+                .cardinal_neighbors()
+                .map(|cell| (cell.coords(), if *cell.value() == 'x' { 5 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+        let manhattan =
+            |coords: &RowCol| end.row.abs_diff(coords.row) + end.col.abs_diff(coords.col);
+
+        let tree = dijkstra(start, successors);
+        assert_eq!(tree.cost(&end), Some(7));
+        let path = dijkstra_to(start, successors, |coords| *coords == end).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path, tree.path_to(&end).unwrap());
+        let path = astar(start, successors, manhattan, |coords| *coords == end).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.states.first(), Some(&start));
+        assert_eq!(path.states.last(), Some(&end));
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let path: Option<Path<u32, u32>> =
+            dijkstra_to(0, |&n| (n < 5).then_some((n + 1, 1)), |&n| n == 10);
+        assert_eq!(path, None);
+    }
+}
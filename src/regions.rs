@@ -0,0 +1,348 @@
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::vec2d::{Cell, RowCol, Vec2d};
+
+/// Which cells count as touching when growing a region
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Connectivity {
+    /// Cells sharing an edge
+    Four,
+    /// Cells sharing an edge or a corner
+    Eight,
+}
+
+impl Connectivity {
+    fn neighbors<'a, T>(&self, cell: &Cell<'a, T>) -> Vec<Cell<'a, T>> {
+        match self {
+            Connectivity::Four => cell.cardinal_neighbors().collect(),
+            Connectivity::Eight => cell.neighbors().collect(),
+        }
+    }
+}
+
+/// A connected set of cells in a grid
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Region {
+    /// The cells of the region in row-major order
+    cells: Vec<RowCol>,
+}
+
+/// The smallest rectangle containing a region, with both corners included
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BoundingBox {
+    pub top_left: RowCol,
+    pub bottom_right: RowCol,
+}
+
+impl BoundingBox {
+    pub fn num_rows(&self) -> usize {
+        self.bottom_right.row - self.top_left.row + 1
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.bottom_right.col - self.top_left.col + 1
+    }
+}
+
+impl Region {
+    fn new(mut cells: Vec<RowCol>) -> Region {
+        cells.sort_by_key(|coords| (coords.row, coords.col));
+        Region { cells }
+    }
+
+    pub fn cells(&self) -> &[RowCol] {
+        &self.cells
+    }
+
+    pub fn contains(&self, coords: &RowCol) -> bool {
+        self.cells
+            .binary_search_by_key(&(coords.row, coords.col), |cell| (cell.row, cell.col))
+            .is_ok()
+    }
+
+    /// The number of cells in the region
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest rectangle containing the region, or `None` if it is empty
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let first = self.cells.first()?;
+        let last = self.cells.last()?;
+        let (min_col, max_col) = self
+            .cells
+            .iter()
+            .fold((first.col, first.col), |(min, max), cell| {
+                (min.min(cell.col), max.max(cell.col))
+            });
+        Some(BoundingBox {
+            top_left: RowCol {
+                row: first.row,
+                col: min_col,
+            },
+            bottom_right: RowCol {
+                row: last.row,
+                col: max_col,
+            },
+        })
+    }
+
+    /// The number of cell edges on the boundary of the region, meaning edges between a cell of
+    /// the region and a cell outside it or the edge of the grid
+    pub fn perimeter(&self) -> usize {
+        let cells: HashSet<_> = self.cells.iter().collect();
+        let outside = |row: Option<usize>, col: Option<usize>| match (row, col) {
+            (Some(row), Some(col)) => !cells.contains(&RowCol { row, col }),
+            _ => true,
+        };
+        self.cells
+            .iter()
+            .map(|&RowCol { row, col }| {
+                [
+                    outside(row.checked_sub(1), Some(col)),
+                    outside(Some(row + 1), Some(col)),
+                    outside(Some(row), col.checked_sub(1)),
+                    outside(Some(row), Some(col + 1)),
+                ]
+                .into_iter()
+                .filter(|&edge| edge)
+                .count()
+            })
+            .sum()
+    }
+}
+
+/// The connected regions of a grid, with the index of the region of every cell
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Components {
+    pub labels: Vec2d<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Components {
+    /// The region containing the cell at `coords`
+    pub fn region_of(&self, coords: &RowCol) -> Option<&Region> {
+        self.labels
+            .get_row_col(coords)
+            .map(|&label| &self.regions[label])
+    }
+}
+
+impl<T> Vec2d<T> {
+    /// The region of cells connected to `seed` whose values satisfy `inside`. The region is
+    /// empty if the seed itself is not inside or not in the grid.
+    pub fn flood_fill(
+        &self,
+        seed: RowCol,
+        connectivity: Connectivity,
+        inside: impl Fn(&T) -> bool,
+    ) -> Region {
+        let Some(seed) = self
+            .get_cell(seed.row, seed.col)
+            .filter(|cell| inside(cell.value()))
+        else {
+            return Region::new(Vec::new());
+        };
+        let mut visited = self.map(|_| false);
+        Region::new(fill(seed, &mut visited, connectivity, |_, cell| {
+            inside(cell.value())
+        }))
+    }
+
+    /// Split the grid into connected regions, where neighbouring cells are in the same region if
+    /// `connected` holds for their values. Regions are numbered in row-major order of their
+    /// first cell.
+    pub fn label_components_by(
+        &self,
+        connectivity: Connectivity,
+        connected: impl Fn(&T, &T) -> bool,
+    ) -> Components {
+        let mut visited = self.map(|_| false);
+        let mut labels = self.map(|_| 0);
+        let mut regions = Vec::new();
+        for seed in self.cells() {
+            if *visited.get(seed.row(), seed.col()).unwrap() {
+                continue;
+            }
+            let region = Region::new(fill(seed, &mut visited, connectivity, |from, to| {
+                connected(from.value(), to.value())
+            }));
+            for coords in region.cells() {
+                *labels.get_mut(coords.row, coords.col).unwrap() = regions.len();
+            }
+            regions.push(region);
+        }
+        Components { labels, regions }
+    }
+}
+
+impl<T: PartialEq> Vec2d<T> {
+    /// Split the grid into connected regions of equal values
+    pub fn label_components(&self, connectivity: Connectivity) -> Components {
+        self.label_components_by(connectivity, |a, b| a == b)
+    }
+}
+
+/// Flood fill over any space of positions, such as a [`Torus`] or a bounded part of a [`Tiled`]
+/// grid. `neighbors` returns the positions next to a position that are in the same region, and
+/// the region must be finite.
+///
+/// [`Torus`]: crate::vec2d::Torus
+/// [`Tiled`]: crate::vec2d::Tiled
+pub fn flood_fill_by<S, I>(seed: S, mut neighbors: impl FnMut(&S) -> I) -> HashSet<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut region = HashSet::from([seed.clone()]);
+    let mut queue = VecDeque::from([seed]);
+    while let Some(pos) = queue.pop_front() {
+        for neighbor in neighbors(&pos) {
+            if region.insert(neighbor.clone()) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    region
+}
+
+/// Breadth-first flood fill from `seed`, marking the cells it reaches. A neighbour is reached
+/// from a cell of the region if `joins` holds for the cell and the neighbour.
+fn fill<T>(
+    seed: Cell<'_, T>,
+    visited: &mut Vec2d<bool>,
+    connectivity: Connectivity,
+    joins: impl Fn(&Cell<'_, T>, &Cell<'_, T>) -> bool,
+) -> Vec<RowCol> {
+    let mut cells = Vec::new();
+    *visited.get_mut(seed.row(), seed.col()).unwrap() = true;
+    let mut queue = VecDeque::from([seed]);
+    while let Some(cell) = queue.pop_front() {
+        cells.push(cell.coords());
+        for neighbor in connectivity.neighbors(&cell) {
+            let seen = visited.get_mut(neighbor.row(), neighbor.col()).unwrap();
+            if !*seen && joins(&cell, &neighbor) {
+                *seen = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod test {
+    use crate::regions::{flood_fill_by, BoundingBox, Connectivity};
+    use crate::vec2d::{Pos, RowCol, Vec2d};
+
+    const GRID: &str = "##..#
+#...#
+..#..
+.#.##";
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = Vec2d::from_lines(GRID.lines()).unwrap();
+        let region = grid.flood_fill(RowCol { row: 0, col: 2 }, Connectivity::Four, |&c| c == '.');
+        assert_eq!(region.size(), 10);
+        assert!(region.contains(&RowCol { row: 3, col: 0 }));
+        assert!(!region.contains(&RowCol { row: 3, col: 2 }));
+        assert_eq!(
+            region.bounding_box(),
+            Some(BoundingBox {
+                top_left: RowCol { row: 0, col: 0 },
+                bottom_right: RowCol { row: 3, col: 4 },
+            })
+        );
+
+        let walls = grid.flood_fill(RowCol { row: 0, col: 0 }, Connectivity::Four, |&c| c == '#');
+        assert_eq!(walls.size(), 3);
+        assert_eq!(walls.perimeter(), 8);
+        let walls = grid.flood_fill(RowCol { row: 3, col: 1 }, Connectivity::Eight, |&c| {
+            c == '#'
+        });
+        assert_eq!(walls.size(), 4);
+
+        assert!(grid
+            .flood_fill(RowCol { row: 0, col: 0 }, Connectivity::Four, |&c| c == '.')
+            .is_empty());
+    }
+
+    #[test]
+    fn test_flood_fill_wrapping() {
+        let grid = Vec2d::from_lines(GRID.lines()).unwrap();
+        let torus = grid.torus();
+        // (3, 2) is cut off inside the grid, but wraps around to (0, 2) at the top
+        let region = flood_fill_by(RowCol { row: 3, col: 2 }, |coords| {
+            torus
+                .cardinal_neighbors(coords)
+                .filter(|next| *torus.base().get_row_col(next).unwrap() == '.')
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(region.len(), 11);
+
+        // Fill the walls within the first two tiles of the top row of tiles
+        let tiled = grid.tiled();
+        let region = flood_fill_by(Pos { row: 0, col: 4 }, |pos| {
+            tiled
+                .cardinal_neighbors(pos)
+                .filter(|next| {
+                    let tile = tiled.tile_index(next);
+                    tile.row == 0 && (0..2).contains(&tile.col) && *tiled.get(next) == '#'
+                })
+                .collect::<Vec<_>>()
+        });
+        // The walls at (0, 4) and (1, 4) join the walls at the top left of the next tile
+        assert_eq!(region.len(), 5);
+        assert!(region.contains(&Pos { row: 1, col: 5 }));
+    }
+
+    #[test]
+    fn test_label_components() {
+        let grid = Vec2d::from_lines(GRID.lines()).unwrap();
+        let components = grid.label_components(Connectivity::Four);
+        assert_eq!(
+            components
+                .regions
+                .iter()
+                .map(|region| region.size())
+                .collect::<Vec<_>>(),
+            vec![3, 10, 2, 1, 1, 1, 2]
+        );
+        assert_eq!(components.labels.get(0, 1), Some(&0));
+        assert_eq!(components.labels.get(3, 0), Some(&1));
+        assert_eq!(
+            components
+                .region_of(&RowCol { row: 3, col: 4 })
+                .unwrap()
+                .cells(),
+            &[RowCol { row: 3, col: 3 }, RowCol { row: 3, col: 4 }]
+        );
+
+        let components = grid.label_components(Connectivity::Eight);
+        assert_eq!(components.regions.len(), 4);
+    }
+
+    #[test]
+    fn test_label_components_non_transitive() {
+        // Neighbouring heights differ by at most 1 along the gradient, but 1 and 4 do not
+        let grid = Vec2d::from_lines(["1234", "9999", "1357"].into_iter())
+            .unwrap()
+            .map(|c| c.to_digit(10).unwrap());
+        let components = grid.label_components_by(Connectivity::Four, |a, b| a.abs_diff(*b) <= 1);
+        assert_eq!(
+            components
+                .regions
+                .iter()
+                .map(|region| region.size())
+                .collect::<Vec<_>>(),
+            vec![4, 4, 1, 1, 1, 1]
+        );
+        assert_eq!(components.labels.get(0, 3), Some(&0));
+    }
+}
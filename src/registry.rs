@@ -0,0 +1,238 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+
+use crate::common::{Answer, Solution, SolveError};
+use crate::params::{Param, Params};
+
+/// A solution that registered itself with [`register!`], so that it can be
+/// looked up by day and part at runtime
+#[derive(Debug)]
+pub struct Registration {
+    day: u8,
+    part: u8,
+    title: &'static str,
+    params: &'static [Param],
+    parse: fn(&[String]) -> Result<ParsedInput, SolveError>,
+    answer: fn(&ParsedInput, &Params) -> Result<Answer, SolveError>,
+}
+
+inventory::collect!(Registration);
+
+impl Registration {
+    pub const fn of<S: Solution>() -> Registration {
+        Registration {
+            day: S::DAY,
+            part: S::PART,
+            title: S::TITLE,
+            params: S::PARAMS,
+            parse: parse_lines::<S>,
+            answer: answer_input::<S>,
+        }
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn part(&self) -> u8 {
+        self.part
+    }
+
+    pub fn title(&self) -> &'static str {
+        self.title
+    }
+
+    /// The parameters used by this part of the puzzle
+    pub fn params(&self) -> impl Iterator<Item = &'static Param> + '_ {
+        self.params.iter().filter(|param| param.part == self.part)
+    }
+
+    /// Check that every overridden parameter is used by this part of the puzzle
+    pub fn check_params(&self, params: &Params) -> Result<(), SolveError> {
+        match params
+            .names()
+            .find(|name| !self.params().any(|param| param.name == *name))
+        {
+            Some(name) => {
+                let available = match self.params().map(|param| param.name).join(", ") {
+                    available if available.is_empty() => "none".to_string(),
+                    available => available,
+                };
+                Err(SolveError::msg(
+                    "read parameter",
+                    format!(
+                        "part {} has no parameter named {} (available: {})",
+                        self.part, name, available
+                    ),
+                )
+                .with_day(self.day))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Parse the input for the puzzle, tagging any error with the day
+    pub fn parse(&self, lines: &[String]) -> Result<ParsedInput, SolveError> {
+        (self.parse)(lines).map_err(|err| err.with_day(self.day))
+    }
+
+    /// Compute the answer from input returned by [`Registration::parse`], tagging any error with
+    /// the day
+    ///
+    /// # Panics
+    ///
+    /// If the input was parsed by a registration for a different day. Both parts of a day share
+    /// the same input.
+    pub fn answer(&self, input: &ParsedInput, params: &Params) -> Result<Answer, SolveError> {
+        (self.answer)(input, params).map_err(|err| err.with_day(self.day))
+    }
+
+    /// Parse the input and solve the puzzle, tagging any error with the day
+    pub fn solve(&self, lines: &[String], params: &Params) -> Result<Answer, SolveError> {
+        self.answer(&self.parse(lines)?, params)
+    }
+}
+
+/// The parsed input of a puzzle, whose type depends on the day
+#[derive(Debug)]
+pub struct ParsedInput(Box<dyn Any>);
+
+fn parse_lines<S: Solution>(lines: &[String]) -> Result<ParsedInput, SolveError> {
+    Ok(ParsedInput(Box::new(S::parse(lines.iter())?)))
+}
+
+fn answer_input<S: Solution>(input: &ParsedInput, params: &Params) -> Result<Answer, SolveError> {
+    let input = input
+        .0
+        .downcast_ref::<S::Input>()
+        .unwrap_or_else(|| panic!("input was not parsed for day {}", S::DAY));
+    S::answer(input, params)
+}
+
+/// Register solutions so that they show up in [`all`] and can be found with [`find`]
+macro_rules! register {
+    ($($solution:ty),+ $(,)?) => {
+        $(
+            inventory::submit! {
+                $crate::registry::Registration::of::<$solution>()
+            }
+        )+
+    };
+}
+
+pub(crate) use register;
+
+/// All registered solutions, ordered by day and then part
+pub fn all() -> Vec<&'static Registration> {
+    inventory::iter::<Registration>
+        .into_iter()
+        .sorted_by_key(|registration| (registration.day, registration.part))
+        .collect()
+}
+
+/// Find the solution for the given day and part
+pub fn find(day: u8, part: u8) -> Result<&'static Registration, UnknownSolution> {
+    inventory::iter::<Registration>
+        .into_iter()
+        .find(|registration| registration.day == day && registration.part == part)
+        .ok_or_else(|| UnknownSolution {
+            day,
+            part,
+            parts: all()
+                .iter()
+                .filter(|registration| registration.day == day)
+                .map(|registration| registration.part)
+                .collect(),
+        })
+}
+
+/// The error returned by [`find`] when no solution is registered for a day and part
+#[derive(Debug)]
+pub struct UnknownSolution {
+    day: u8,
+    part: u8,
+    /// The parts that are registered for the requested day
+    parts: Vec<u8>,
+}
+
+impl Display for UnknownSolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.parts.as_slice() {
+            [] => write!(f, "there is no solution for day {}", self.day)?,
+            [part] => write!(
+                f,
+                "day {} has no part {}, only part {}",
+                self.day, self.part, part
+            )?,
+            parts => write!(
+                f,
+                "day {} has no part {}, only parts {}",
+                self.day,
+                self.part,
+                parts.iter().join(" and ")
+            )?,
+        }
+        write!(f, " (use `list` to see the available solutions)")
+    }
+}
+
+impl std::error::Error for UnknownSolution {}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::params::Params;
+    use crate::registry::{all, find};
+
+    #[test]
+    fn test_all_days_registered() {
+        let registrations = all();
+        assert_eq!(registrations.len(), 49);
+        assert!(registrations
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| (a.day(), a.part()) < (b.day(), b.part())));
+        assert_eq!(find(25, 1).unwrap().title(), "Snowverload");
+    }
+
+    #[test]
+    fn test_find_unknown() {
+        assert_eq!(
+            find(25, 2).unwrap_err().to_string(),
+            "day 25 has no part 2, only part 1 (use `list` to see the available solutions)"
+        );
+        assert_eq!(
+            find(26, 1).unwrap_err().to_string(),
+            "there is no solution for day 26 (use `list` to see the available solutions)"
+        );
+        assert_eq!(
+            find(1, 3).unwrap_err().to_string(),
+            "day 1 has no part 3, only parts 1 and 2 (use `list` to see the available solutions)"
+        );
+    }
+
+    #[test]
+    fn test_check_params() {
+        let mut params = Params::default();
+        params.set("steps", "6");
+        assert!(find(21, 1).unwrap().check_params(&params).is_ok());
+        assert_eq!(
+            find(21, 2).unwrap().check_params(&params).unwrap_err().to_string(),
+            "day 21: failed to read parameter: part 2 has no parameter named steps (available: total_steps)"
+        );
+    }
+
+    #[test]
+    fn test_solve_through_registry() {
+        let lines = ["1abc2", "pqr3stu8vwx"].map(String::from);
+        let params = Params::default();
+        let registration = find(1, 1).unwrap();
+        assert_eq!(registration.solve(&lines, &params).unwrap(), "50");
+        let input = registration.parse(&lines).unwrap();
+        assert_eq!(registration.answer(&input, &params).unwrap(), "50");
+        assert_eq!(find(1, 2).unwrap().answer(&input, &params).unwrap(), "50");
+    }
+}
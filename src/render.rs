@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Write};
+
+use crate::vec2d::{Direction, Pos, RowCol, Vec2d};
+
+/// A terminal colour for highlighting tiles
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    /// The ANSI escape code setting this colour as the foreground colour
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+/// A set of cells drawn over the grid, each with an optional replacement character
+struct Overlay {
+    cells: HashMap<RowCol, Option<char>>,
+    color: Option<Color>,
+}
+
+/// A grid drawn as characters, built with [`Vec2d::render`] and printed with [`Display`].
+///
+/// Overlays are drawn in the order they are added, so later overlays win where they overlap.
+/// Colours are only written when ANSI output is turned on, so overlays meant to be read without
+/// colours should replace the characters of their cells.
+pub struct Render<'a, T> {
+    grid: &'a Vec2d<T>,
+    tile: Box<dyn Fn(&T) -> char + 'a>,
+    overlays: Vec<Overlay>,
+    ansi: bool,
+    rulers: bool,
+}
+
+impl<T> Vec2d<T> {
+    /// Draw the grid with `tile` giving the character of each value
+    pub fn render<'a>(&'a self, tile: impl Fn(&T) -> char + 'a) -> Render<'a, T> {
+        Render {
+            grid: self,
+            tile: Box::new(tile),
+            overlays: Vec::new(),
+            ansi: false,
+            rulers: false,
+        }
+    }
+}
+
+impl<'a, T> Render<'a, T> {
+    /// Draw `glyph` over every cell in `cells`
+    pub fn mark(mut self, cells: impl IntoIterator<Item = RowCol>, glyph: char) -> Self {
+        self.overlays.push(Overlay {
+            cells: cells
+                .into_iter()
+                .map(|coords| (coords, Some(glyph)))
+                .collect(),
+            color: None,
+        });
+        self
+    }
+
+    /// Colour the tiles of every cell in `cells`, leaving their characters alone
+    pub fn highlight(mut self, cells: impl IntoIterator<Item = RowCol>, color: Color) -> Self {
+        self.overlays.push(Overlay {
+            cells: cells.into_iter().map(|coords| (coords, None)).collect(),
+            color: Some(color),
+        });
+        self
+    }
+
+    /// Draw a path as arrows pointing from each cell to the next, in `color`. Cells that are not
+    /// next to the following cell, and the last cell, keep their tiles and are only coloured.
+    pub fn path(mut self, path: &[RowCol], color: Color) -> Self {
+        let mut cells: HashMap<_, _> = path
+            .windows(2)
+            .map(|step| {
+                let offset = Pos::from(step[1]) - Pos::from(step[0]);
+                let arrow = Direction::ALL
+                    .into_iter()
+                    .find(|direction| direction.offset() == offset)
+                    .map(char::from);
+                (step[0], arrow)
+            })
+            .collect();
+        if let Some(&last) = path.last() {
+            cells.insert(last, None);
+        }
+        self.overlays.push(Overlay {
+            cells,
+            color: Some(color),
+        });
+        self
+    }
+
+    /// Write colours as ANSI escape codes
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// Number the rows down the left side and the columns across the top, with one line of
+    /// digits per decimal place of the column numbers
+    pub fn rulers(mut self, rulers: bool) -> Self {
+        self.rulers = rulers;
+        self
+    }
+
+    /// The character and colour of a cell, after applying the overlays
+    fn glyph(&self, coords: &RowCol, value: &T) -> (char, Option<Color>) {
+        self.overlays
+            .iter()
+            .filter_map(|overlay| Some((overlay.cells.get(coords)?, overlay.color)))
+            .fold(
+                ((self.tile)(value), None),
+                |(glyph, color), (overlay_glyph, overlay_color)| {
+                    (overlay_glyph.unwrap_or(glyph), overlay_color.or(color))
+                },
+            )
+    }
+}
+
+impl<'a, T> Display for Render<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let num_rows = self.grid.num_rows();
+        let num_cols = self.grid.num_cols();
+        let gutter = if self.rulers {
+            num_rows.saturating_sub(1).to_string().len() + 1
+        } else {
+            0
+        };
+        if self.rulers {
+            let places = num_cols.saturating_sub(1).to_string().len() as u32;
+            for place in (0..places).rev() {
+                write!(f, "{:gutter$}", "")?;
+                for col in 0..num_cols {
+                    let digit = col / 10usize.pow(place) % 10;
+                    f.write_char(char::from_digit(digit as u32, 10).unwrap())?;
+                }
+                f.write_char('\n')?;
+            }
+        }
+        for row in 0..num_rows {
+            if self.rulers {
+                write!(f, "{:>width$} ", row, width = gutter - 1)?;
+            }
+            for col in 0..num_cols {
+                let coords = RowCol { row, col };
+                let (glyph, color) = self.glyph(&coords, self.grid.get_row_col(&coords).unwrap());
+                match color.filter(|_| self.ansi) {
+                    Some(color) => write!(f, "\x1b[{}m{}\x1b[0m", color.ansi_code(), glyph)?,
+                    None => f.write_char(glyph)?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::render::Color;
+    use crate::vec2d::{RowCol, Vec2d};
+
+    fn grid() -> Vec2d<char> {
+        Vec2d::from_lines(["..#", "#..", "..."].into_iter()).unwrap()
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let grid = grid();
+        assert_eq!(grid.render(|&c| c).to_string(), "..#\n#..\n...\n");
+        let walls = grid.map(|&c| c == '#');
+        assert_eq!(
+            walls
+                .render(|&wall| if wall { '█' } else { ' ' })
+                .to_string(),
+            "  █\n█  \n   \n"
+        );
+    }
+
+    #[test]
+    fn test_render_overlays() {
+        let grid = grid();
+        let path = [
+            RowCol { row: 0, col: 0 },
+            RowCol { row: 0, col: 1 },
+            RowCol { row: 1, col: 1 },
+            RowCol { row: 1, col: 2 },
+            RowCol { row: 2, col: 2 },
+        ];
+        let render = grid
+            .render(|&c| c)
+            .mark([RowCol { row: 2, col: 0 }, RowCol { row: 2, col: 2 }], 'O')
+            .path(&path, Color::Green);
+        assert_eq!(render.to_string(), ">v#\n#>v\nO.O\n");
+
+        let render = grid
+            .render(|&c| c)
+            .highlight([RowCol { row: 0, col: 2 }], Color::Red)
+            .ansi(true);
+        assert_eq!(render.to_string(), "..\x1b[31m#\x1b[0m\n#..\n...\n");
+    }
+
+    #[test]
+    fn test_render_rulers() {
+        let grid = Vec2d::with_shape_and_value(11, 12, '.');
+        let render = grid.render(|&c| c).rulers(true).to_string();
+        let lines: Vec<_> = render.lines().collect();
+        assert_eq!(lines[0], "   000000000011");
+        assert_eq!(lines[1], "   012345678901");
+        assert_eq!(lines[2], " 0 ............");
+        assert_eq!(lines[12], "10 ............");
+    }
+}
@@ -1,11 +1,17 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter, Write};
 use std::hash::{Hash, Hasher};
-use std::ops::Deref;
+use std::num::TryFromIntError;
+use std::ops::{Add, AddAssign, Deref, DerefMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
 
-#[derive(Eq, PartialEq, Clone)]
+/// A rectangular grid stored as a single buffer in row-major order
+#[derive(Eq, PartialEq, Clone, Hash)]
 pub struct Vec2d<T> {
-    pub(crate) inner: Vec<Vec<T>>,
+    cells: Vec<T>,
+    num_rows: usize,
+    num_cols: usize,
 }
 
 #[derive(Copy)]
@@ -15,7 +21,14 @@ pub struct Cell<'a, T> {
     pub(crate) col: usize,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+/// A mutable handle to a cell of a grid, which can move around the grid and update values
+pub struct CellMut<'a, T> {
+    parent: &'a mut Vec2d<T>,
+    row: usize,
+    col: usize,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct RowCol {
     pub row: usize,
     pub col: usize,
@@ -33,6 +46,158 @@ impl From<(usize, usize)> for RowCol {
     }
 }
 
+/// Signed grid coordinates, for positions that may lie outside a grid
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Pos {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl From<RowCol> for Pos {
+    fn from(RowCol { row, col }: RowCol) -> Self {
+        Pos {
+            row: row as i64,
+            col: col as i64,
+        }
+    }
+}
+
+impl TryFrom<Pos> for RowCol {
+    type Error = TryFromIntError;
+
+    fn try_from(Pos { row, col }: Pos) -> Result<Self, Self::Error> {
+        Ok(RowCol {
+            row: row.try_into()?,
+            col: col.try_into()?,
+        })
+    }
+}
+
+/// The difference between two [`Pos`]itions
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Offset {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Pos {
+    pub const ORIGIN: Pos = Pos { row: 0, col: 0 };
+
+    pub fn new(row: i64, col: i64) -> Pos {
+        Pos { row, col }
+    }
+
+    /// The coordinates of this position in a grid, or `None` if either is negative
+    pub fn to_row_col(&self) -> Option<RowCol> {
+        RowCol::try_from(*self).ok()
+    }
+
+    /// The number of steps between two positions when moving up, down, left and right
+    pub fn manhattan_distance(&self, other: &Pos) -> u64 {
+        (*other - *self).manhattan_len()
+    }
+}
+
+impl Offset {
+    pub fn new(row: i64, col: i64) -> Offset {
+        Offset { row, col }
+    }
+
+    /// The number of steps up, down, left and right needed to move by this offset
+    pub fn manhattan_len(&self) -> u64 {
+        self.row.unsigned_abs() + self.col.unsigned_abs()
+    }
+}
+
+impl From<Direction> for Offset {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+impl Add<Offset> for Pos {
+    type Output = Pos;
+
+    fn add(self, offset: Offset) -> Pos {
+        Pos {
+            row: self.row + offset.row,
+            col: self.col + offset.col,
+        }
+    }
+}
+
+impl AddAssign<Offset> for Pos {
+    fn add_assign(&mut self, offset: Offset) {
+        *self = *self + offset;
+    }
+}
+
+impl Sub<Offset> for Pos {
+    type Output = Pos;
+
+    fn sub(self, offset: Offset) -> Pos {
+        self + -offset
+    }
+}
+
+impl SubAssign<Offset> for Pos {
+    fn sub_assign(&mut self, offset: Offset) {
+        *self = *self - offset;
+    }
+}
+
+impl Sub for Pos {
+    type Output = Offset;
+
+    fn sub(self, other: Pos) -> Offset {
+        Offset {
+            row: self.row - other.row,
+            col: self.col - other.col,
+        }
+    }
+}
+
+impl Add for Offset {
+    type Output = Offset;
+
+    fn add(self, other: Offset) -> Offset {
+        Offset {
+            row: self.row + other.row,
+            col: self.col + other.col,
+        }
+    }
+}
+
+impl Sub for Offset {
+    type Output = Offset;
+
+    fn sub(self, other: Offset) -> Offset {
+        self + -other
+    }
+}
+
+impl Neg for Offset {
+    type Output = Offset;
+
+    fn neg(self) -> Offset {
+        Offset {
+            row: -self.row,
+            col: -self.col,
+        }
+    }
+}
+
+impl Mul<i64> for Offset {
+    type Output = Offset;
+
+    fn mul(self, factor: i64) -> Offset {
+        Offset {
+            row: self.row * factor,
+            col: self.col * factor,
+        }
+    }
+}
+
 /// Represents a contiguous set of cells within a specific row
 /// It is guaranteed that the row value of these cells is the same
 pub struct CellRowRange<'a, T> {
@@ -42,6 +207,15 @@ pub struct CellRowRange<'a, T> {
     last_col: usize,
 }
 
+/// Represents a contiguous set of cells within a specific column
+/// It is guaranteed that the column value of these cells is the same
+pub struct CellColRange<'a, T> {
+    parent: &'a Vec2d<T>,
+    col: usize,
+    first_row: usize,
+    last_row: usize,
+}
+
 impl<'a, T> Clone for Cell<'a, T> {
     fn clone(&self) -> Self {
         self.parent.get_cell(self.row(), self.col()).unwrap()
@@ -77,51 +251,116 @@ impl<'a, T> Hash for Cell<'a, T> {
 }
 
 impl<T> Vec2d<T> {
+    /// Build a grid from its rows, checking that there is at least one row and that every row
+    /// has the same, non-zero, length
+    pub fn try_from_rows(rows: Vec<Vec<T>>) -> Result<Vec2d<T>, ShapeError> {
+        let num_rows = rows.len();
+        let num_cols = match rows.first() {
+            None => return Err(ShapeError::Empty),
+            Some(first) if first.is_empty() => return Err(ShapeError::EmptyRow { row: 0 }),
+            Some(first) => first.len(),
+        };
+        let mut cells = Vec::with_capacity(num_rows * num_cols);
+        for (row, values) in rows.into_iter().enumerate() {
+            if values.len() != num_cols {
+                return Err(ShapeError::Ragged {
+                    row,
+                    len: values.len(),
+                    expected: num_cols,
+                });
+            }
+            cells.extend(values);
+        }
+        Ok(Vec2d {
+            cells,
+            num_rows,
+            num_cols,
+        })
+    }
+
+    fn index(&self, row: usize, col: usize) -> Option<usize> {
+        (row < self.num_rows && col < self.num_cols).then_some(row * self.num_cols + col)
+    }
+
     pub fn get(&self, row: usize, col: usize) -> Option<&T> {
-        self.inner.get(row).and_then(|row| row.get(col))
+        self.index(row, col).map(|index| &self.cells[index])
     }
 
     pub fn get_row_col(&self, row_col: &RowCol) -> Option<&T> {
         self.get(row_col.row, row_col.col)
     }
 
+    /// The value at a signed position, or `None` if it is outside the grid
+    pub fn get_pos(&self, pos: &Pos) -> Option<&T> {
+        pos.to_row_col()
+            .and_then(|row_col| self.get_row_col(&row_col))
+    }
+
     pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
-        self.inner.get_mut(row).and_then(|row| row.get_mut(col))
+        self.index(row, col).map(|index| &mut self.cells[index])
+    }
+
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<Cell<'_, T>> {
+        self.index(row, col).map(|_index| Cell {
+            parent: self,
+            row,
+            col,
+        })
     }
 
-    pub fn get_cell(&self, row: usize, col: usize) -> Option<Cell<T>> {
-        self.get(row, col).map(|_value| Cell {
+    pub fn get_cell_mut(&mut self, row: usize, col: usize) -> Option<CellMut<'_, T>> {
+        self.index(row, col).map(|_index| CellMut {
             parent: self,
             row,
             col,
         })
     }
 
+    /// Swap the values of two cells, returning false if either is outside the grid
+    pub fn swap(&mut self, a: &RowCol, b: &RowCol) -> bool {
+        match (self.index(a.row, a.col), self.index(b.row, b.col)) {
+            (Some(a), Some(b)) => {
+                self.cells.swap(a, b);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn get_row(&self, row: usize) -> Option<&[T]> {
-        self.inner.get(row).map(|row| row.as_slice())
+        (row < self.num_rows).then(|| &self.cells[row * self.num_cols..(row + 1) * self.num_cols])
+    }
+
+    /// The rows of the grid, from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.num_rows).map(|row| self.get_row(row).unwrap())
     }
 
-    pub fn get_col_cells(&self, col: usize) -> impl Iterator<Item = Cell<T>> {
-        (0..self.inner.len()).filter_map(move |row| self.get_cell(row, col))
+    pub fn get_col_cells(&self, col: usize) -> impl Iterator<Item = Cell<'_, T>> {
+        (0..self.num_rows).filter_map(move |row| self.get_cell(row, col))
     }
 
     pub fn get_col(&self, col: usize) -> impl Iterator<Item = &T> {
-        (0..self.inner.len()).filter_map(move |row| self.get(row, col))
+        (0..self.num_rows).filter_map(move |row| self.get(row, col))
     }
 
-    pub fn cells(&self) -> impl Iterator<Item = Cell<T>> {
-        self.inner
-            .iter()
-            .enumerate()
-            .flat_map(move |(row, row_val)| {
-                row_val.iter().enumerate().map(move |(col, _value)| Cell {
-                    parent: self,
-                    row,
-                    col,
-                })
+    /// Every cell of the grid in row-major order
+    pub fn cells(&self) -> impl Iterator<Item = Cell<'_, T>> {
+        (0..self.num_rows)
+            .flat_map(move |row| (0..self.num_cols).map(move |col| (row, col)))
+            .map(move |(row, col)| Cell {
+                parent: self,
+                row,
+                col,
             })
     }
-    pub fn get_range(&self, row: usize, first_col: usize, last_col: usize) -> CellRowRange<T> {
+
+    /// The values of the grid in row-major order
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn get_range(&self, row: usize, first_col: usize, last_col: usize) -> CellRowRange<'_, T> {
         CellRowRange {
             parent: self,
             row,
@@ -132,52 +371,145 @@ impl<T> Vec2d<T> {
 
     pub fn map<F: Fn(&T) -> S, S>(&self, f: F) -> Vec2d<S> {
         Vec2d {
-            inner: self
-                .inner
-                .iter()
-                .map(|row| row.iter().map(&f).collect())
-                .collect(),
+            cells: self.cells.iter().map(f).collect(),
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
         }
     }
 
     pub fn num_rows(&self) -> usize {
-        self.inner.len()
+        self.num_rows
     }
 
-    pub fn first_num_cols(&self) -> usize {
-        self.inner.get(0).map(|row| row.len()).unwrap_or(0)
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
     }
 
-    pub fn top_left_cell(&self) -> Option<Cell<T>> {
+    pub fn top_left_cell(&self) -> Option<Cell<'_, T>> {
         self.get_cell(0, 0)
     }
 
-    pub fn bottom_right_cell(&self) -> Option<Cell<T>> {
-        let last_row = self.inner.len() - 1;
-        let Some(last_col) = self.inner.get(last_row).map(|row| row.len() - 1) else {
-            return None;
-        };
-
-        self.get_cell(last_row, last_col)
+    pub fn bottom_right_cell(&self) -> Option<Cell<'_, T>> {
+        self.get_cell(self.num_rows.checked_sub(1)?, self.num_cols.checked_sub(1)?)
     }
 }
 
-impl<T: Copy> Vec2d<T> {
+impl<T: Clone> Vec2d<T> {
     pub fn with_shape_and_value(rows: usize, cols: usize, value: T) -> Vec2d<T> {
         Vec2d {
-            inner: (0..rows).map(|_| vec![value; cols]).collect(),
+            cells: vec![value; rows * cols],
+            num_rows: rows,
+            num_cols: cols,
         }
     }
 }
 
 impl Vec2d<char> {
-    pub fn from_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Vec2d<char> {
-        Vec2d {
-            inner: lines.map(|line| line.as_ref().chars().collect()).collect(),
+    /// Build a grid with one row per line, checking that the lines form a rectangle
+    pub fn from_lines(
+        lines: impl Iterator<Item = impl AsRef<str>>,
+    ) -> Result<Vec2d<char>, ShapeError> {
+        Vec2d::try_from_rows(lines.map(|line| line.as_ref().chars().collect()).collect())
+    }
+
+    /// Convert each character with `parse`, reporting the coordinates of the first one that
+    /// fails
+    pub fn try_map<T, E>(&self, parse: impl Fn(char) -> Result<T, E>) -> Result<Vec2d<T>, TileError>
+    where
+        E: Into<anyhow::Error>,
+    {
+        let cells = self
+            .cells()
+            .map(|cell| {
+                parse(*cell.value()).map_err(|err| TileError {
+                    coords: cell.coords(),
+                    tile: *cell.value(),
+                    source: err.into(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Vec2d {
+            cells,
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
+        })
+    }
+
+    /// Convert each character to a tile with its `TryFrom<char>` implementation
+    pub fn parse_tiles<T>(&self) -> Result<Vec2d<T>, TileError>
+    where
+        T: TryFrom<char>,
+        T::Error: Into<anyhow::Error>,
+    {
+        self.try_map(T::try_from)
+    }
+}
+
+/// The reason that rows could not be made into a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeError {
+    /// There were no rows
+    Empty,
+    /// A row had no values
+    EmptyRow { row: usize },
+    /// A row had a different length to the first row
+    Ragged {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+}
+
+impl ShapeError {
+    /// The row that caused the error, if any
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            ShapeError::Empty => None,
+            ShapeError::EmptyRow { row } | ShapeError::Ragged { row, .. } => Some(*row),
+        }
+    }
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::Empty => f.write_str("the grid has no rows"),
+            ShapeError::EmptyRow { row } => write!(f, "row {} is empty", row),
+            ShapeError::Ragged { row, len, expected } => write!(
+                f,
+                "row {} has length {}, but the first row has length {}",
+                row, len, expected
+            ),
         }
     }
 }
 
+impl std::error::Error for ShapeError {}
+
+/// A character in a grid that could not be converted to a tile
+#[derive(Debug)]
+pub struct TileError {
+    pub coords: RowCol,
+    pub tile: char,
+    pub source: anyhow::Error,
+}
+
+impl Display for TileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown tile {:?} at row {}, column {}",
+            self.tile, self.coords.row, self.coords.col
+        )
+    }
+}
+
+impl std::error::Error for TileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
 impl<T> Vec2d<Option<T>> {
     pub fn flat_get(&self, row: usize, col: usize) -> Option<&T> {
         self.get(row, col).and_then(|val| val.as_ref())
@@ -190,7 +522,7 @@ impl<T> Vec2d<Option<T>> {
 
 impl<T: Debug> Debug for Vec2d<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in &self.inner {
+        for row in self.rows() {
             row.fmt(f)?;
             f.write_char('\n')?;
         }
@@ -207,9 +539,9 @@ impl<'a> Display for CellRowRange<'a, char> {
     }
 }
 
-impl<T> Cell<'_, T> {
+impl<'a, T> Cell<'a, T> {
     pub fn value(&self) -> &T {
-        &self.parent.inner[self.row][self.col]
+        self.parent.get(self.row, self.col).unwrap()
     }
 
     pub fn coords(&self) -> RowCol {
@@ -219,6 +551,10 @@ impl<T> Cell<'_, T> {
         }
     }
 
+    pub fn pos(&self) -> Pos {
+        Pos::from(self.coords())
+    }
+
     pub fn row(&self) -> usize {
         self.row
     }
@@ -227,63 +563,105 @@ impl<T> Cell<'_, T> {
         self.col
     }
 
-    pub fn get_top(&self) -> Option<Cell<T>> {
+    pub fn get_top(&self) -> Option<Cell<'a, T>> {
         self.get_diff(-1, 0)
     }
 
-    pub fn get_below(&self) -> Option<Cell<T>> {
+    pub fn get_below(&self) -> Option<Cell<'a, T>> {
         self.get_diff(1, 0)
     }
 
-    pub fn get_left(&self) -> Option<Cell<T>> {
+    pub fn get_left(&self) -> Option<Cell<'a, T>> {
         self.get_diff(0, -1)
     }
 
-    pub fn get_right(&self) -> Option<Cell<T>> {
+    pub fn get_right(&self) -> Option<Cell<'a, T>> {
         self.get_diff(0, 1)
     }
 
-    pub fn cardinal_neighbors(&self) -> impl Iterator<Item = Cell<T>> {
+    pub fn cardinal_neighbors(&self) -> impl Iterator<Item = Cell<'a, T>> {
+        self.cardinal_neighbors_with_direction()
+            .map(|(_, cell)| cell)
+    }
+
+    /// The cells above, right of, below and left of this one, with the direction to each
+    pub fn cardinal_neighbors_with_direction(
+        &self,
+    ) -> impl Iterator<Item = (Direction, Cell<'a, T>)> {
+        let cell = self.clone();
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| Some((direction, cell.get_offset(direction.offset())?)))
+    }
+
+    /// The eight cells surrounding this one, column by column from the top left: the column to
+    /// the left from top to bottom, then the cells above and below, then the column to the right
+    pub fn neighbors(&self) -> impl Iterator<Item = Cell<'a, T>> {
+        let cell = self.clone();
         [
-            self.get_top(),
-            self.get_right(),
-            self.get_below(),
-            self.get_left(),
+            Direction8::UpLeft,
+            Direction8::Left,
+            Direction8::DownLeft,
+            Direction8::Up,
+            Direction8::Down,
+            Direction8::UpRight,
+            Direction8::Right,
+            Direction8::DownRight,
         ]
         .into_iter()
-        .flatten()
-    }
-
-    pub fn neighbors(&self) -> impl Iterator<Item = Cell<T>> {
-        let row = self.row as i32;
-        let col = self.col as i32;
-        let neighbor_indices = [
-            (row - 1, col - 1),
-            (row, col - 1),
-            (row + 1, col - 1),
-            (row - 1, col),
-            (row + 1, col),
-            (row - 1, col + 1),
-            (row, col + 1),
-            (row + 1, col + 1),
-        ];
-
-        neighbor_indices
+        .filter_map(move |direction| cell.get_offset(direction.offset()))
+    }
+
+    /// The eight cells surrounding this one, clockwise from the cell above, with the direction
+    /// to each
+    pub fn neighbors_with_direction(&self) -> impl Iterator<Item = (Direction8, Cell<'a, T>)> {
+        let cell = self.clone();
+        Direction8::ALL
             .into_iter()
-            .filter_map(|(row, col)| {
-                let row = usize::try_from(row);
-                let col = usize::try_from(col);
+            .filter_map(move |direction| Some((direction, cell.get_offset(direction.offset())?)))
+    }
 
-                match (row, col) {
-                    (Ok(row), Ok(col)) => Some((row, col)),
-                    _ => None,
-                }
-            })
-            .filter_map(|(row, col)| self.parent.get_cell(row, col))
+    /// The cell at the given offset from this one, if it exists
+    pub fn get_offset(&self, offset: Offset) -> Option<Cell<'a, T>> {
+        let coords = (self.pos() + offset).to_row_col()?;
+        self.parent.get_cell(coords.row, coords.col)
+    }
+
+    /// The neighbouring cell in `direction`, if it exists
+    pub fn get_next(&self, direction: Direction) -> Option<Cell<'a, T>> {
+        self.get_offset(direction.offset())
+    }
+
+    /// The cells in a line from this one in `direction` to the edge of the grid, not including
+    /// this one
+    pub fn ray(&self, direction: Direction) -> impl Iterator<Item = Cell<'a, T>> {
+        std::iter::successors(self.get_next(direction), move |cell| {
+            cell.get_next(direction)
+        })
+    }
+
+    /// The nearest cell in `direction` from this one whose value satisfies `predicate`
+    pub fn find_first_in<P>(&self, direction: Direction, predicate: P) -> Option<Cell<'a, T>>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.ray(direction).find(|cell| predicate(cell.value()))
+    }
+
+    /// The farthest cell that can be reached from this one by moving in `direction` over cells
+    /// whose values satisfy `predicate`, which is this cell if the next one does not
+    pub fn find_last_satisfying_in<P>(&self, direction: Direction, predicate: P) -> Cell<'a, T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.ray(direction)
+            .take_while(|cell| predicate(cell.value()))
+            .last()
+            .unwrap_or_else(|| self.clone())
     }
 
     /// find the first cell in the row of the current cell
-    pub fn first_cell_in_row(&self) -> Cell<T> {
+    pub fn first_cell_in_row(&self) -> Cell<'a, T> {
         Cell {
             parent: self.parent,
             row: self.row,
@@ -292,85 +670,81 @@ impl<T> Cell<'_, T> {
     }
 
     /// return the last cell in the current row
-    pub fn last_cell_in_row(&self) -> Cell<T> {
+    pub fn last_cell_in_row(&self) -> Cell<'a, T> {
         Cell {
             parent: self.parent,
             row: self.row,
-            col: self.parent.inner[self.row].len() - 1,
+            col: self.parent.num_cols - 1,
         }
     }
 
     /// returns the row of the current cell
     pub fn get_row(&self) -> &[T] {
-        self.parent.inner[self.row].as_slice()
+        self.parent.get_row(self.row).unwrap()
     }
 
-    pub fn find_first_before<P>(&self, predicate: P) -> Option<Cell<T>>
+    pub fn find_first_before<P>(&self, predicate: P) -> Option<Cell<'a, T>>
     where
         P: Fn(&T) -> bool,
     {
-        let row_vec = &self.parent.inner[self.row];
-        for (i, value) in row_vec.iter().enumerate().take(self.col).rev() {
-            if predicate(value) {
-                return self.parent.get_cell(self.row, i);
-            }
-        }
-        None
+        self.find_first_in(Direction::Left, predicate)
     }
 
-    pub fn find_first_after<P>(&self, predicate: P) -> Option<Cell<T>>
+    pub fn find_first_after<P>(&self, predicate: P) -> Option<Cell<'a, T>>
     where
         P: Fn(&T) -> bool,
     {
-        let row_vec = &self.parent.inner[self.row];
-        for (i, value) in row_vec.iter().enumerate().skip(self.col + 1) {
-            if predicate(value) {
-                return self.parent.get_cell(self.row, i);
-            }
-        }
-        None
+        self.find_first_in(Direction::Right, predicate)
     }
 
     /// Find the longest contiguous range of neighbors of this cell in the same row satisfying
     /// the given predicate.
-    pub fn find_contiguous_satisfying<P>(&self, predicate: P) -> CellRowRange<T>
+    pub fn find_contiguous_satisfying<P>(&self, predicate: P) -> CellRowRange<'a, T>
     where
         P: Fn(&T) -> bool,
     {
-        let first_cell = self.find_first_before(|val| !predicate(val));
-        let first_cell = first_cell
-            .as_ref()
-            .and_then(|cell| cell.next_col())
-            .unwrap_or_else(|| self.first_cell_in_row());
-
-        let last_cell = self.find_first_after(|val| !predicate(val));
-        let last_cell = last_cell
-            .as_ref()
-            .and_then(|cell| cell.prev_col())
-            .unwrap_or_else(|| self.last_cell_in_row());
-
         CellRowRange {
             parent: self.parent,
             row: self.row,
-            first_col: first_cell.col,
-            last_col: last_cell.col,
+            first_col: self
+                .find_last_satisfying_in(Direction::Left, &predicate)
+                .col,
+            last_col: self
+                .find_last_satisfying_in(Direction::Right, &predicate)
+                .col,
+        }
+    }
+
+    /// Find the longest contiguous range of neighbors of this cell in the same column satisfying
+    /// the given predicate.
+    pub fn find_contiguous_satisfying_in_col<P>(&self, predicate: P) -> CellColRange<'a, T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        CellColRange {
+            parent: self.parent,
+            col: self.col,
+            first_row: self.find_last_satisfying_in(Direction::Up, &predicate).row,
+            last_row: self
+                .find_last_satisfying_in(Direction::Down, &predicate)
+                .row,
         }
     }
 
-    pub fn prev_col(&self) -> Option<Cell<T>> {
+    pub fn prev_col(&self) -> Option<Cell<'a, T>> {
         if self.col == 0 {
             None
         } else {
             self.parent.get_cell(self.row, self.col - 1)
         }
     }
-    pub fn next_col(&self) -> Option<Cell<T>> {
+    pub fn next_col(&self) -> Option<Cell<'a, T>> {
         self.parent.get_cell(self.row, self.col + 1)
     }
 
     /// Modify the row and column by the specified value and return the cell at the coordiante,
     /// if it exists
-    pub fn get_diff(&self, row: isize, col: isize) -> Option<Cell<T>> {
+    pub fn get_diff(&self, row: isize, col: isize) -> Option<Cell<'a, T>> {
         self.row
             .checked_add_signed(row)
             .and_then(|row| self.col.checked_add_signed(col).map(|col| (row, col)))
@@ -391,16 +765,18 @@ impl<'a, T> PartialOrd for Cell<'a, T> {
 }
 
 impl<'a, T> CellRowRange<'a, T> {
-    pub fn cells(&self) -> impl Iterator<Item = Cell<T>> {
-        (self.first_col..=self.last_col).map(|col| {
-            self.parent
-                .get_cell(self.row, col)
+    pub fn cells(&self) -> impl Iterator<Item = Cell<'a, T>> {
+        let parent = self.parent;
+        let row = self.row;
+        (self.first_col..=self.last_col).map(move |col| {
+            parent
+                .get_cell(row, col)
                 .ok_or_else(|| anyhow::Error::msg("Invalid row range"))
                 .unwrap()
         })
     }
 
-    pub fn first(&self) -> Cell<T> {
+    pub fn first(&self) -> Cell<'a, T> {
         Cell {
             parent: self.parent,
             row: self.row,
@@ -408,7 +784,7 @@ impl<'a, T> CellRowRange<'a, T> {
         }
     }
 
-    pub fn last(&self) -> Cell<T> {
+    pub fn last(&self) -> Cell<'a, T> {
         Cell {
             parent: self.parent,
             row: self.row,
@@ -417,7 +793,7 @@ impl<'a, T> CellRowRange<'a, T> {
     }
 
     pub fn as_slice(&self) -> &[T] {
-        &self.parent.inner[self.row].as_slice()[self.first_col..=self.last_col]
+        &self.parent.get_row(self.row).unwrap()[self.first_col..=self.last_col]
     }
 }
 
@@ -432,38 +808,1679 @@ impl<'a, T> PartialEq for CellRowRange<'a, T> {
 
 impl<'a, T> Eq for CellRowRange<'a, T> {}
 
-impl<'a, T> PartialOrd for CellRowRange<'a, T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.first()
-            .partial_cmp(&other.first())
-            .or_else(|| self.last().partial_cmp(&other.last()))
+impl<'a, T> CellColRange<'a, T> {
+    pub fn cells(&self) -> impl Iterator<Item = Cell<'a, T>> {
+        let parent = self.parent;
+        let col = self.col;
+        (self.first_row..=self.last_row).map(move |row| parent.get_cell(row, col).unwrap())
+    }
+
+    pub fn first(&self) -> Cell<'a, T> {
+        Cell {
+            parent: self.parent,
+            row: self.first_row,
+            col: self.col,
+        }
+    }
+
+    pub fn last(&self) -> Cell<'a, T> {
+        Cell {
+            parent: self.parent,
+            row: self.last_row,
+            col: self.col,
+        }
+    }
+
+    /// The values of the cells from top to bottom
+    pub fn values(&self) -> impl Iterator<Item = &'a T> {
+        let parent = self.parent;
+        let col = self.col;
+        (self.first_row..=self.last_row).map(move |row| parent.get(row, col).unwrap())
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+impl<'a, T> PartialEq for CellColRange<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.parent, other.parent)
+            && self.col == other.col
+            && self.first_row == other.first_row
+            && self.last_row == other.last_row
+    }
 }
 
-impl Direction {
-    pub fn next(&self, RowCol { row, col }: RowCol) -> Option<RowCol> {
-        match self {
-            Direction::Up => row.checked_sub(1).map(|row| RowCol { row, col }),
-            Direction::Down => Some(RowCol { row: row + 1, col }),
-            Direction::Left => col.checked_sub(1).map(|col| RowCol { row, col }),
-            Direction::Right => Some(RowCol { row, col: col + 1 }),
+impl<'a, T> Eq for CellColRange<'a, T> {}
+
+impl<'a, T> CellMut<'a, T> {
+    pub fn value(&self) -> &T {
+        self.parent.get(self.row, self.col).unwrap()
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        self.parent.get_mut(self.row, self.col).unwrap()
+    }
+
+    /// Replace the value of the cell, returning the old value
+    pub fn set(&mut self, value: T) -> T {
+        std::mem::replace(self.value_mut(), value)
+    }
+
+    /// Read-only access to the cell, to use the navigation and search methods of [`Cell`]
+    pub fn as_cell(&self) -> Cell<'_, T> {
+        Cell {
+            parent: self.parent,
+            row: self.row,
+            col: self.col,
         }
     }
 
-    pub fn opposite(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
+    /// Give up the handle, keeping the mutable borrow of the value
+    pub fn into_value(self) -> &'a mut T {
+        self.parent.get_mut(self.row, self.col).unwrap()
+    }
+
+    pub fn coords(&self) -> RowCol {
+        RowCol {
+            row: self.row,
+            col: self.col,
         }
     }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    pub fn get_top(&mut self) -> Option<CellMut<'_, T>> {
+        self.get_diff(-1, 0)
+    }
+
+    pub fn get_below(&mut self) -> Option<CellMut<'_, T>> {
+        self.get_diff(1, 0)
+    }
+
+    pub fn get_left(&mut self) -> Option<CellMut<'_, T>> {
+        self.get_diff(0, -1)
+    }
+
+    pub fn get_right(&mut self) -> Option<CellMut<'_, T>> {
+        self.get_diff(0, 1)
+    }
+
+    /// The neighbouring cell in `direction`, if it exists
+    pub fn get_next(&mut self, direction: Direction) -> Option<CellMut<'_, T>> {
+        let next = direction.next(self.coords())?;
+        self.parent.get_cell_mut(next.row, next.col)
+    }
+
+    /// Borrow the cell at the given offset from this one, if it exists
+    pub fn get_diff(&mut self, row: isize, col: isize) -> Option<CellMut<'_, T>> {
+        let (row, col) = self.offset(row, col)?;
+        self.parent.get_cell_mut(row, col)
+    }
+
+    /// Move the handle by the given offset, returning false and staying put if the new
+    /// coordinates are outside the grid
+    pub fn move_diff(&mut self, row: isize, col: isize) -> bool {
+        match self.offset(row, col) {
+            Some((row, col)) => {
+                self.row = row;
+                self.col = col;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the handle to the neighbouring cell in `direction`, returning false and staying put
+    /// if there is no such cell
+    pub fn move_next(&mut self, direction: Direction) -> bool {
+        let Offset { row, col } = direction.offset();
+        self.move_diff(row as isize, col as isize)
+    }
+
+    /// Swap the value of this cell with the value at `other`, returning false if `other` is
+    /// outside the grid
+    pub fn swap(&mut self, other: &RowCol) -> bool {
+        let coords = self.coords();
+        self.parent.swap(&coords, other)
+    }
+
+    fn offset(&self, row: isize, col: isize) -> Option<(usize, usize)> {
+        let row = self.row.checked_add_signed(row)?;
+        let col = self.col.checked_add_signed(col)?;
+        self.parent.index(row, col).map(|_index| (row, col))
+    }
+}
+
+impl<'a, T> Deref for CellMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value()
+    }
+}
+
+impl<'a, T> DerefMut for CellMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value_mut()
+    }
+}
+
+impl<'a, T> PartialOrd for CellRowRange<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.first()
+            .partial_cmp(&other.first())
+            .or_else(|| self.last().partial_cmp(&other.last()))
+    }
+}
+
+/// A mapping from the coordinates of a transformed or cropped grid back to the coordinates of
+/// the grid it was made from. Transforms are cheap to combine, and a [`View`] uses one to read
+/// a grid in another orientation without copying it.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Transform {
+    /// The parent coordinates of row 0, column 0
+    origin: (isize, isize),
+    /// The change in parent coordinates when moving down one row
+    row_step: (isize, isize),
+    /// The change in parent coordinates when moving right one column
+    col_step: (isize, isize),
+    num_rows: usize,
+    num_cols: usize,
+}
+
+impl Transform {
+    /// The transform that leaves a grid with the given shape unchanged
+    pub fn identity(num_rows: usize, num_cols: usize) -> Transform {
+        Transform {
+            origin: (0, 0),
+            row_step: (1, 0),
+            col_step: (0, 1),
+            num_rows,
+            num_cols,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// The coordinates in the parent grid of the given transformed coordinates, if they are
+    /// within the transformed grid
+    pub fn to_parent(&self, row: usize, col: usize) -> Option<RowCol> {
+        if row >= self.num_rows || col >= self.num_cols {
+            return None;
+        }
+        let (row, col) = (row as isize, col as isize);
+        Some(RowCol {
+            row: (self.origin.0 + row * self.row_step.0 + col * self.col_step.0) as usize,
+            col: (self.origin.1 + row * self.row_step.1 + col * self.col_step.1) as usize,
+        })
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(self) -> Transform {
+        Transform {
+            origin: self.origin,
+            row_step: self.col_step,
+            col_step: self.row_step,
+            num_rows: self.num_cols,
+            num_cols: self.num_rows,
+        }
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(self) -> Transform {
+        let last_col = self.num_cols.saturating_sub(1) as isize;
+        Transform {
+            origin: (
+                self.origin.0 + last_col * self.col_step.0,
+                self.origin.1 + last_col * self.col_step.1,
+            ),
+            col_step: (-self.col_step.0, -self.col_step.1),
+            ..self
+        }
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(self) -> Transform {
+        let last_row = self.num_rows.saturating_sub(1) as isize;
+        Transform {
+            origin: (
+                self.origin.0 + last_row * self.row_step.0,
+                self.origin.1 + last_row * self.row_step.1,
+            ),
+            row_step: (-self.row_step.0, -self.row_step.1),
+            ..self
+        }
+    }
+
+    /// Rotate a quarter turn clockwise, so the left column becomes the top row
+    pub fn rotate_90(self) -> Transform {
+        self.transpose().flip_horizontal()
+    }
+
+    pub fn rotate_180(self) -> Transform {
+        self.flip_horizontal().flip_vertical()
+    }
+
+    /// Rotate a quarter turn anticlockwise, so the right column becomes the top row
+    pub fn rotate_270(self) -> Transform {
+        self.transpose().flip_vertical()
+    }
+
+    /// Crop to the rectangle with its top left at `top`, `left`, if it fits
+    pub fn sub_grid(self, top: usize, left: usize, rows: usize, cols: usize) -> Option<Transform> {
+        if top.checked_add(rows)? > self.num_rows || left.checked_add(cols)? > self.num_cols {
+            return None;
+        }
+        let (top, left) = (top as isize, left as isize);
+        Some(Transform {
+            origin: (
+                self.origin.0 + top * self.row_step.0 + left * self.col_step.0,
+                self.origin.1 + top * self.row_step.1 + left * self.col_step.1,
+            ),
+            num_rows: rows,
+            num_cols: cols,
+            ..self
+        })
+    }
+}
+
+/// A borrowed, possibly transformed or cropped, view of a grid
+pub struct View<'a, T> {
+    parent: &'a Vec2d<T>,
+    transform: Transform,
+}
+
+impl<'a, T> Clone for View<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for View<'a, T> {}
+
+impl<'a, T> View<'a, T> {
+    pub fn num_rows(&self) -> usize {
+        self.transform.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.transform.num_cols
+    }
+
+    /// The mapping from this view's coordinates to the coordinates of the underlying grid
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        self.transform
+            .to_parent(row, col)
+            .and_then(|coords| self.parent.get_row_col(&coords))
+    }
+
+    /// The cell of the underlying grid at the given coordinates of the view
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<Cell<'a, T>> {
+        self.transform
+            .to_parent(row, col)
+            .and_then(|coords| self.parent.get_cell(coords.row, coords.col))
+    }
+
+    pub fn get_row(&self, row: usize) -> impl Iterator<Item = &'a T> + 'a {
+        let view = *self;
+        (0..self.num_cols()).filter_map(move |col| view.get(row, col))
+    }
+
+    pub fn get_col(&self, col: usize) -> impl Iterator<Item = &'a T> + 'a {
+        let view = *self;
+        (0..self.num_rows()).filter_map(move |row| view.get(row, col))
+    }
+
+    /// The values of the view in row-major order
+    pub fn values(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let view = *self;
+        (0..self.num_rows()).flat_map(move |row| view.get_row(row))
+    }
+
+    pub fn transpose(self) -> View<'a, T> {
+        self.with_transform(self.transform.transpose())
+    }
+
+    pub fn flip_horizontal(self) -> View<'a, T> {
+        self.with_transform(self.transform.flip_horizontal())
+    }
+
+    pub fn flip_vertical(self) -> View<'a, T> {
+        self.with_transform(self.transform.flip_vertical())
+    }
+
+    pub fn rotate_90(self) -> View<'a, T> {
+        self.with_transform(self.transform.rotate_90())
+    }
+
+    pub fn rotate_180(self) -> View<'a, T> {
+        self.with_transform(self.transform.rotate_180())
+    }
+
+    pub fn rotate_270(self) -> View<'a, T> {
+        self.with_transform(self.transform.rotate_270())
+    }
+
+    pub fn sub_grid(
+        self,
+        top: usize,
+        left: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Option<View<'a, T>> {
+        Some(self.with_transform(self.transform.sub_grid(top, left, rows, cols)?))
+    }
+
+    fn with_transform(self, transform: Transform) -> View<'a, T> {
+        View {
+            parent: self.parent,
+            transform,
+        }
+    }
+}
+
+impl<'a, T: Clone> View<'a, T> {
+    /// Copy the view into a new grid
+    pub fn to_vec2d(&self) -> Vec2d<T> {
+        Vec2d {
+            cells: self.values().cloned().collect(),
+            num_rows: self.num_rows(),
+            num_cols: self.num_cols(),
+        }
+    }
+}
+
+impl<'a, T: Debug> Debug for View<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.num_rows() {
+            f.debug_list().entries(self.get_row(row)).finish()?;
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/// Views of the grid in other orientations, which borrow the grid rather than copying it
+impl<T> Vec2d<T> {
+    pub fn view(&self) -> View<'_, T> {
+        View {
+            parent: self,
+            transform: Transform::identity(self.num_rows, self.num_cols),
+        }
+    }
+
+    pub fn transpose(&self) -> View<'_, T> {
+        self.view().transpose()
+    }
+
+    pub fn flip_horizontal(&self) -> View<'_, T> {
+        self.view().flip_horizontal()
+    }
+
+    pub fn flip_vertical(&self) -> View<'_, T> {
+        self.view().flip_vertical()
+    }
+
+    pub fn rotate_90(&self) -> View<'_, T> {
+        self.view().rotate_90()
+    }
+
+    pub fn rotate_180(&self) -> View<'_, T> {
+        self.view().rotate_180()
+    }
+
+    pub fn rotate_270(&self) -> View<'_, T> {
+        self.view().rotate_270()
+    }
+
+    /// A view of the rectangle with its top left at `top`, `left`, if it fits in the grid
+    pub fn sub_grid(
+        &self,
+        top: usize,
+        left: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Option<View<'_, T>> {
+        self.view().sub_grid(top, left, rows, cols)
+    }
+}
+
+/// A grid repeated forever in every direction, addressed with signed coordinates. Position
+/// (0, 0) is the top left of the base grid, which is tile (0, 0).
+pub struct Tiled<'a, T> {
+    base: &'a Vec2d<T>,
+}
+
+/// A grid whose edges wrap around, so that moving off one side comes back on the other
+pub struct Torus<'a, T> {
+    base: &'a Vec2d<T>,
+}
+
+impl<'a, T> Clone for Tiled<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Tiled<'a, T> {}
+
+impl<'a, T> Clone for Torus<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Torus<'a, T> {}
+
+impl<'a, T> Tiled<'a, T> {
+    pub fn base(&self) -> &'a Vec2d<T> {
+        self.base
+    }
+
+    /// The value at `pos`, taken from the matching cell of the base grid
+    pub fn get(&self, pos: &Pos) -> &'a T {
+        let coords = self.base_coords(pos);
+        self.base.get(coords.row, coords.col).unwrap()
+    }
+
+    /// The cell of the base grid that `pos` is a copy of
+    pub fn base_cell(&self, pos: &Pos) -> Cell<'a, T> {
+        let coords = self.base_coords(pos);
+        self.base.get_cell(coords.row, coords.col).unwrap()
+    }
+
+    /// The coordinates within the base grid that `pos` is a copy of
+    pub fn base_coords(&self, pos: &Pos) -> RowCol {
+        RowCol {
+            row: pos.row.rem_euclid(self.base.num_rows as i64) as usize,
+            col: pos.col.rem_euclid(self.base.num_cols as i64) as usize,
+        }
+    }
+
+    /// Which copy of the base grid `pos` is in, counted in tiles down and right from the base
+    pub fn tile_index(&self, pos: &Pos) -> Pos {
+        Pos {
+            row: pos.row.div_euclid(self.base.num_rows as i64),
+            col: pos.col.div_euclid(self.base.num_cols as i64),
+        }
+    }
+
+    /// The positions above, right of, below and left of `pos`
+    pub fn cardinal_neighbors(&self, pos: &Pos) -> impl Iterator<Item = Pos> {
+        let pos = *pos;
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| pos + direction.offset())
+    }
+}
+
+impl<'a, T> Torus<'a, T> {
+    pub fn base(&self) -> &'a Vec2d<T> {
+        self.base
+    }
+
+    /// The value at `pos`, wrapping around the edges
+    pub fn get(&self, pos: &Pos) -> &'a T {
+        let coords = self.wrap(pos);
+        self.base.get(coords.row, coords.col).unwrap()
+    }
+
+    /// The coordinates in the grid of `pos` after wrapping around the edges
+    pub fn wrap(&self, pos: &Pos) -> RowCol {
+        self.base.tiled().base_coords(pos)
+    }
+
+    /// How many times `pos` wraps around the grid vertically and horizontally
+    pub fn tile_index(&self, pos: &Pos) -> Pos {
+        self.base.tiled().tile_index(pos)
+    }
+
+    /// The cells above, right of, below and left of `coords`, wrapping around the edges
+    pub fn cardinal_neighbors(&self, coords: &RowCol) -> impl Iterator<Item = RowCol> + 'a {
+        let torus = *self;
+        self.base
+            .tiled()
+            .cardinal_neighbors(&Pos::from(*coords))
+            .map(move |pos| torus.wrap(&pos))
+    }
+}
+
+/// Wrapping and repeating views of the grid. Both panic on lookups if the grid is empty.
+impl<T> Vec2d<T> {
+    pub fn tiled(&self) -> Tiled<'_, T> {
+        Tiled { base: self }
+    }
+
+    pub fn torus(&self) -> Torus<'_, T> {
+        Torus { base: self }
+    }
+}
+
+/// A grid of booleans packed 64 cells to a word, with each row starting on a new word. Whole-grid
+/// operations work a word at a time, so stepping a set of positions in every direction costs a
+/// few bitwise operations per row.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    num_rows: usize,
+    num_cols: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// A grid with every cell unset
+    pub fn new(num_rows: usize, num_cols: usize) -> BitGrid {
+        let words_per_row = num_cols.div_ceil(64);
+        BitGrid {
+            words: vec![0; num_rows * words_per_row],
+            num_rows,
+            num_cols,
+            words_per_row,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    fn word_and_mask(&self, row: usize, col: usize) -> Option<(usize, u64)> {
+        if row >= self.num_rows || col >= self.num_cols {
+            return None;
+        }
+        Some((row * self.words_per_row + col / 64, 1 << (col % 64)))
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<bool> {
+        let (word, mask) = self.word_and_mask(row, col)?;
+        Some(self.words[word] & mask != 0)
+    }
+
+    /// Whether the cell at `coords` is set, which is false outside the grid
+    pub fn contains(&self, coords: &RowCol) -> bool {
+        self.get(coords.row, coords.col).unwrap_or(false)
+    }
+
+    /// Set the cell at `coords` to `value`, returning its old value
+    pub fn set(&mut self, coords: &RowCol, value: bool) -> bool {
+        let (word, mask) = self
+            .word_and_mask(coords.row, coords.col)
+            .expect("coordinates outside the grid");
+        let old = self.words[word] & mask != 0;
+        if value {
+            self.words[word] |= mask;
+        } else {
+            self.words[word] &= !mask;
+        }
+        old
+    }
+
+    /// The number of set cells
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Unset every cell, keeping the allocation
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Set every cell that is set in `other`, which must have the same shape
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.zip_words(other, |word, other| word | other);
+    }
+
+    /// Unset every cell that is not set in `other`, which must have the same shape
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.zip_words(other, |word, other| word & other);
+    }
+
+    /// Unset every cell that is set in `other`, which must have the same shape
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.zip_words(other, |word, other| word & !other);
+    }
+
+    fn zip_words(&mut self, other: &BitGrid, op: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            (self.num_rows, self.num_cols),
+            (other.num_rows, other.num_cols),
+            "grids of different shapes"
+        );
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            *word = op(*word, other);
+        }
+    }
+
+    /// The grid with every set cell moved one step in `direction`, dropping the cells that move
+    /// off the edge
+    pub fn shifted(&self, direction: Direction) -> BitGrid {
+        let mut shifted = BitGrid::new(self.num_rows, self.num_cols);
+        shifted.shift_from(self, direction);
+        shifted
+    }
+
+    /// Overwrite this grid with `other` moved one step in `direction`, reusing the allocation.
+    /// The grids must have the same shape.
+    pub fn shift_from(&mut self, other: &BitGrid, direction: Direction) {
+        assert_eq!(
+            (self.num_rows, self.num_cols),
+            (other.num_rows, other.num_cols),
+            "grids of different shapes"
+        );
+        let width = self.words_per_row;
+        if self.words.is_empty() {
+            return;
+        }
+        match direction {
+            Direction::Up | Direction::Down => {
+                // Every row but one moves, and the row left behind at the edge is blank
+                let moved = self.words.len() - width;
+                let (from, to, blank) = if direction == Direction::Up {
+                    (width..width + moved, 0..moved, moved..moved + width)
+                } else {
+                    (0..moved, width..width + moved, 0..width)
+                };
+                self.words[to].copy_from_slice(&other.words[from]);
+                self.words[blank].fill(0);
+            }
+            Direction::Left | Direction::Right => {
+                let last_word_mask = self.last_word_mask();
+                for (row, other_row) in self
+                    .words
+                    .chunks_exact_mut(width)
+                    .zip(other.words.chunks_exact(width))
+                {
+                    for i in 0..width {
+                        row[i] = if direction == Direction::Right {
+                            let carry = if i > 0 { other_row[i - 1] >> 63 } else { 0 };
+                            (other_row[i] << 1) | carry
+                        } else {
+                            let carry = other_row.get(i + 1).map_or(0, |next| next << 63);
+                            (other_row[i] >> 1) | carry
+                        };
+                    }
+                    row[width - 1] &= last_word_mask;
+                }
+            }
+        }
+    }
+
+    /// The bits of the last word of a row that are inside the grid
+    fn last_word_mask(&self) -> u64 {
+        match self.num_cols % 64 {
+            0 => u64::MAX,
+            used => (1 << used) - 1,
+        }
+    }
+
+    /// The coordinates of the set cells in row-major order
+    pub fn ones(&self) -> impl Iterator<Item = RowCol> + '_ {
+        (0..self.num_rows).flat_map(move |row| {
+            (0..self.num_cols)
+                .filter(move |&col| self.get(row, col) == Some(true))
+                .map(move |col| RowCol { row, col })
+        })
+    }
+
+    pub fn to_vec2d(&self) -> Vec2d<bool> {
+        let mut grid = Vec2d::with_shape_and_value(self.num_rows, self.num_cols, false);
+        for coords in self.ones() {
+            *grid.get_mut(coords.row, coords.col).unwrap() = true;
+        }
+        grid
+    }
+}
+
+impl From<&Vec2d<bool>> for BitGrid {
+    fn from(grid: &Vec2d<bool>) -> BitGrid {
+        let mut bits = BitGrid::new(grid.num_rows(), grid.num_cols());
+        for cell in grid.cells().filter(|cell| *cell.value()) {
+            bits.set(&cell.coords(), true);
+        }
+        bits
+    }
+}
+
+impl Debug for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.num_rows {
+            for col in 0..self.num_cols {
+                f.write_char(if self.get(row, col) == Some(true) {
+                    '#'
+                } else {
+                    '.'
+                })?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/// A grid over unbounded signed coordinates that only stores the occupied positions, for maps
+/// that grow in every direction from their start
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos, T>,
+    bounds: Option<Bounds>,
+}
+
+/// The smallest rectangle of signed positions containing every occupied position of a
+/// [`SparseGrid`], with both corners included
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Bounds {
+    pub top_left: Pos,
+    pub bottom_right: Pos,
+}
+
+impl Bounds {
+    fn around(pos: Pos) -> Bounds {
+        Bounds {
+            top_left: pos,
+            bottom_right: pos,
+        }
+    }
+
+    fn include(&mut self, pos: &Pos) {
+        self.top_left.row = self.top_left.row.min(pos.row);
+        self.top_left.col = self.top_left.col.min(pos.col);
+        self.bottom_right.row = self.bottom_right.row.max(pos.row);
+        self.bottom_right.col = self.bottom_right.col.max(pos.col);
+    }
+
+    pub fn num_rows(&self) -> usize {
+        (self.bottom_right.row - self.top_left.row + 1) as usize
+    }
+
+    pub fn num_cols(&self) -> usize {
+        (self.bottom_right.col - self.top_left.col + 1) as usize
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        (self.top_left.row..=self.bottom_right.row).contains(&pos.row)
+            && (self.top_left.col..=self.bottom_right.col).contains(&pos.col)
+    }
+
+    /// The coordinates of `pos` in a dense grid of the bounds, or `None` if it is outside them
+    pub fn to_row_col(&self, pos: &Pos) -> Option<RowCol> {
+        if !self.contains(pos) {
+            return None;
+        }
+        (Pos::ORIGIN + (*pos - self.top_left)).to_row_col()
+    }
+
+    /// The position of the cell at `coords` in a dense grid of the bounds
+    pub fn to_pos(&self, coords: &RowCol) -> Pos {
+        self.top_left + (Pos::from(*coords) - Pos::ORIGIN)
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Store `value` at `pos`, returning the value that was there before
+    pub fn insert(&mut self, pos: Pos, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(&pos),
+            None => self.bounds = Some(Bounds::around(pos)),
+        }
+        self.cells.insert(pos, value)
+    }
+
+    /// Take the value at `pos` out of the grid, shrinking the bounds if it was on their edge
+    pub fn remove(&mut self, pos: &Pos) -> Option<T> {
+        let value = self.cells.remove(pos)?;
+        let bounds = self.bounds.unwrap();
+        let on_edge = [bounds.top_left.row, bounds.bottom_right.row].contains(&pos.row)
+            || [bounds.top_left.col, bounds.bottom_right.col].contains(&pos.col);
+        if on_edge {
+            self.bounds = self.cells.keys().fold(None, |bounds, pos| match bounds {
+                Some(mut bounds) => {
+                    bounds.include(pos);
+                    Some(bounds)
+                }
+                None => Some(Bounds::around(*pos)),
+            });
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, pos: &Pos) -> Option<&T> {
+        self.cells.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    pub fn get_cell(&self, pos: Pos) -> Option<SparseCell<'_, T>> {
+        self.contains(&pos)
+            .then_some(SparseCell { parent: self, pos })
+    }
+
+    /// The occupied cells, in no particular order
+    pub fn cells(&self) -> impl Iterator<Item = SparseCell<'_, T>> {
+        self.cells
+            .keys()
+            .map(|&pos| SparseCell { parent: self, pos })
+    }
+
+    /// The number of occupied positions
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest rectangle containing every occupied position, or `None` if there are none
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// A dense grid of the bounds, with `convert` giving the value of each cell from the value
+    /// stored at its position, if any. The top left of the bounds is at (0, 0), and
+    /// [`Bounds::to_pos`] maps the dense coordinates back to positions.
+    pub fn to_vec2d_with<U>(&self, convert: impl Fn(Option<&T>) -> U) -> Vec2d<U> {
+        let Some(bounds) = self.bounds else {
+            return Vec2d {
+                cells: Vec::new(),
+                num_rows: 0,
+                num_cols: 0,
+            };
+        };
+        let (num_rows, num_cols) = (bounds.num_rows(), bounds.num_cols());
+        let cells = (0..num_rows)
+            .flat_map(|row| (0..num_cols).map(move |col| RowCol { row, col }))
+            .map(|coords| convert(self.get(&bounds.to_pos(&coords))))
+            .collect();
+        Vec2d {
+            cells,
+            num_rows,
+            num_cols,
+        }
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// A dense grid of the bounds, with `empty` at the unoccupied positions
+    pub fn to_vec2d(&self, empty: T) -> Vec2d<T> {
+        self.to_vec2d_with(|value| value.unwrap_or(&empty).clone())
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new()
+    }
+}
+
+impl<T> FromIterator<(Pos, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Pos, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (pos, value) in iter {
+            grid.insert(pos, value);
+        }
+        grid
+    }
+}
+
+/// An occupied position of a [`SparseGrid`], navigating to the occupied positions around it like
+/// a [`Cell`]
+pub struct SparseCell<'a, T> {
+    parent: &'a SparseGrid<T>,
+    pos: Pos,
+}
+
+impl<'a, T> Clone for SparseCell<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for SparseCell<'a, T> {}
+
+impl<'a, T> SparseCell<'a, T> {
+    pub fn value(&self) -> &'a T {
+        self.parent.get(&self.pos).unwrap()
+    }
+
+    pub fn pos(&self) -> Pos {
+        self.pos
+    }
+
+    pub fn get_next(&self, direction: Direction) -> Option<SparseCell<'a, T>> {
+        self.get_offset(direction.offset())
+    }
+
+    pub fn get_offset(&self, offset: Offset) -> Option<SparseCell<'a, T>> {
+        self.parent.get_cell(self.pos + offset)
+    }
+
+    /// The occupied positions up, right, down and left of this one
+    pub fn cardinal_neighbors(&self) -> impl Iterator<Item = SparseCell<'a, T>> {
+        self.cardinal_neighbors_with_direction()
+            .map(|(_, cell)| cell)
+    }
+
+    pub fn cardinal_neighbors_with_direction(
+        &self,
+    ) -> impl Iterator<Item = (Direction, SparseCell<'a, T>)> {
+        let cell = *self;
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| Some((direction, cell.get_next(direction)?)))
+    }
+
+    /// The occupied positions among the eight around this one, clockwise from up
+    pub fn neighbors(&self) -> impl Iterator<Item = SparseCell<'a, T>> {
+        let cell = *self;
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |direction| cell.get_offset(direction.offset()))
+    }
+}
+
+impl<'a, T> Deref for SparseCell<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The coordinates one step in this direction, or `None` if they would be negative
+    pub fn next(&self, row_col: RowCol) -> Option<RowCol> {
+        (Pos::from(row_col) + self.offset()).to_row_col()
+    }
+
+    /// The offset of one step in this direction
+    pub fn offset(&self) -> Offset {
+        match self {
+            Direction::Up => Offset { row: -1, col: 0 },
+            Direction::Down => Offset { row: 1, col: 0 },
+            Direction::Left => Offset { row: 0, col: -1 },
+            Direction::Right => Offset { row: 0, col: 1 },
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// The direction after turning a quarter turn anticlockwise
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// The direction after turning a quarter turn clockwise
+    pub fn turn_right(&self) -> Direction {
+        self.turn_left().opposite()
+    }
+
+    /// The direction after bouncing off a `/` mirror
+    pub fn reflect_forward(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    /// The direction after bouncing off a `\` mirror
+    pub fn reflect_backward(&self) -> Direction {
+        self.reflect_forward().opposite()
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    /// Parse a direction written as `U`/`D`/`L`/`R`, an arrow `^`/`v`/`<`/`>` or a compass
+    /// point `N`/`S`/`W`/`E`
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' | '^' | 'N' => Ok(Direction::Up),
+            'D' | 'v' | 'S' => Ok(Direction::Down),
+            'L' | '<' | 'W' => Ok(Direction::Left),
+            'R' | '>' | 'E' => Ok(Direction::Right),
+            _ => Err(anyhow::Error::msg("Invalid direction").context(value)),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => {
+                Err(anyhow::Error::msg("Expected a single direction character")
+                    .context(s.to_string()))
+            }
+        }
+    }
+}
+
+impl From<Direction> for char {
+    /// The arrow pointing in the direction
+    fn from(direction: Direction) -> char {
+        match direction {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+}
+
+/// One of the eight directions to a neighbouring cell, including diagonals
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from up
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    /// The offset of one step in this direction
+    pub fn offset(&self) -> Offset {
+        let (row, col) = match self {
+            Direction8::Up => (-1, 0),
+            Direction8::UpRight => (-1, 1),
+            Direction8::Right => (0, 1),
+            Direction8::DownRight => (1, 1),
+            Direction8::Down => (1, 0),
+            Direction8::DownLeft => (1, -1),
+            Direction8::Left => (0, -1),
+            Direction8::UpLeft => (-1, -1),
+        };
+        Offset { row, col }
+    }
+
+    pub fn opposite(&self) -> Direction8 {
+        let index = Direction8::ALL.iter().position(|d| d == self).unwrap();
+        Direction8::ALL[(index + 4) % 8]
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.cardinal().is_none()
+    }
+
+    /// The matching up, down, left or right direction, or `None` for diagonals
+    pub fn cardinal(&self) -> Option<Direction> {
+        match self {
+            Direction8::Up => Some(Direction::Up),
+            Direction8::Right => Some(Direction::Right),
+            Direction8::Down => Some(Direction::Down),
+            Direction8::Left => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Direction8::Up,
+            Direction::Right => Direction8::Right,
+            Direction::Down => Direction8::Down,
+            Direction::Left => Direction8::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vec2d::{
+        BitGrid, Bounds, Direction, Direction8, Offset, Pos, RowCol, ShapeError, SparseGrid, Vec2d,
+        View,
+    };
+
+    #[test]
+    fn test_row_major_layout() {
+        let grid = Vec2d::from_lines(["abc", "def"].into_iter()).unwrap();
+        assert_eq!((grid.num_rows(), grid.num_cols()), (2, 3));
+        assert_eq!(grid.as_slice(), &['a', 'b', 'c', 'd', 'e', 'f']);
+        assert_eq!(grid.get_row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.get_row(2), None);
+        assert_eq!(grid.get(0, 3), None);
+        assert_eq!(grid.get_col(2).collect::<String>(), "cf");
+        assert_eq!(
+            grid.cells().map(|cell| *cell.value()).collect::<String>(),
+            "abcdef"
+        );
+        assert_eq!(
+            grid.bottom_right_cell().unwrap().coords(),
+            RowCol { row: 1, col: 2 }
+        );
+        assert_eq!(grid.get_cell(1, 1).unwrap().get_row(), &['d', 'e', 'f']);
+    }
+
+    #[test]
+    fn test_get_mut_and_map() {
+        let mut grid = Vec2d::with_shape_and_value(2, 2, 0);
+        *grid.get_mut(1, 0).unwrap() = 5;
+        assert_eq!(grid.as_slice(), &[0, 0, 5, 0]);
+        assert_eq!(grid.map(|value| value * 2).as_slice(), &[0, 0, 10, 0]);
+    }
+
+    #[test]
+    fn test_cell_mut() {
+        let mut grid = Vec2d::from_lines(["abc", "def"].into_iter()).unwrap();
+        let mut cell = grid.get_cell_mut(0, 0).unwrap();
+        assert_eq!(cell.set('x'), 'a');
+        *cell.get_right().unwrap() = 'y';
+        assert!(cell.get_top().is_none());
+        assert!(cell.move_next(Direction::Down));
+        assert!(!cell.move_next(Direction::Left));
+        assert_eq!(cell.coords(), RowCol { row: 1, col: 0 });
+        *cell.value_mut() = 'z';
+        assert_eq!(cell.as_cell().get_right().map(|right| *right), Some('e'));
+        assert!(cell.swap(&RowCol { row: 1, col: 2 }));
+        assert!(!cell.swap(&RowCol { row: 2, col: 0 }));
+        assert_eq!(grid.as_slice(), &['x', 'y', 'c', 'f', 'e', 'z']);
+    }
+
+    fn view_to_string(view: View<'_, char>) -> String {
+        (0..view.num_rows())
+            .map(|row| view.get_row(row).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = Vec2d::from_lines(["abc", "def"].into_iter()).unwrap();
+        assert_eq!(view_to_string(grid.view()), "abc/def");
+        assert_eq!(view_to_string(grid.transpose()), "ad/be/cf");
+        assert_eq!(view_to_string(grid.rotate_90()), "da/eb/fc");
+        assert_eq!(view_to_string(grid.rotate_180()), "fed/cba");
+        assert_eq!(view_to_string(grid.rotate_270()), "cf/be/ad");
+        assert_eq!(view_to_string(grid.flip_horizontal()), "cba/fed");
+        assert_eq!(view_to_string(grid.flip_vertical()), "def/abc");
+        assert_eq!(
+            view_to_string(grid.rotate_90().rotate_90().rotate_90().rotate_90()),
+            "abc/def"
+        );
+        assert_eq!(
+            grid.rotate_90().get_cell(0, 1).unwrap().coords(),
+            RowCol { row: 0, col: 0 }
+        );
+        assert_eq!(
+            grid.rotate_90().to_vec2d(),
+            grid.rotate_270().rotate_180().to_vec2d()
+        );
+    }
+
+    #[test]
+    fn test_sub_grid() {
+        let grid = Vec2d::from_lines(["abcd", "efgh", "ijkl"].into_iter()).unwrap();
+        let sub = grid.sub_grid(1, 1, 2, 2).unwrap();
+        assert_eq!(view_to_string(sub), "fg/jk");
+        assert_eq!(view_to_string(sub.rotate_90()), "jf/kg");
+        assert_eq!(sub.get(2, 0), None);
+        assert_eq!(
+            view_to_string(grid.rotate_180().sub_grid(0, 0, 1, 3).unwrap()),
+            "lkj"
+        );
+        assert!(grid.sub_grid(2, 0, 2, 1).is_none());
+        assert_eq!(sub.get_col(1).collect::<String>(), "gk");
+    }
+
+    #[test]
+    fn test_tiled_and_torus() {
+        let grid = Vec2d::from_lines(["abc", "def"].into_iter()).unwrap();
+        let tiled = grid.tiled();
+        let pos = Pos { row: -1, col: 7 };
+        assert_eq!(*tiled.get(&pos), 'e');
+        assert_eq!(tiled.base_coords(&pos), RowCol { row: 1, col: 1 });
+        assert_eq!(tiled.tile_index(&pos), Pos { row: -1, col: 2 });
+        assert_eq!(tiled.base_cell(&pos).coords(), RowCol { row: 1, col: 1 });
+        assert_eq!(
+            tiled
+                .cardinal_neighbors(&Pos { row: 0, col: 0 })
+                .map(|pos| *tiled.get(&pos))
+                .collect::<String>(),
+            "dbdc"
+        );
+
+        let torus = grid.torus();
+        assert_eq!(torus.wrap(&pos), RowCol { row: 1, col: 1 });
+        assert_eq!(*torus.get(&Pos { row: 2, col: -1 }), 'c');
+        assert_eq!(
+            torus
+                .cardinal_neighbors(&RowCol { row: 0, col: 2 })
+                .collect::<Vec<_>>(),
+            vec![
+                RowCol { row: 1, col: 2 },
+                RowCol { row: 0, col: 0 },
+                RowCol { row: 1, col: 2 },
+                RowCol { row: 0, col: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pos_and_offset() {
+        let pos = Pos::new(2, -3);
+        let offset = Direction::Down.offset() * 4 + Direction::Left.offset();
+        assert_eq!(offset, Offset::new(4, -1));
+        assert_eq!(pos + offset, Pos::new(6, -4));
+        assert_eq!(pos - offset, Pos::new(-2, -2));
+        assert_eq!((pos + offset) - pos, offset);
+        assert_eq!(-offset, Offset::new(-4, 1));
+        assert_eq!(pos.manhattan_distance(&Pos::ORIGIN), 5);
+        assert_eq!(offset.manhattan_len(), 5);
+
+        let mut moved = pos;
+        moved += Offset::from(Direction::Right) * 3;
+        assert_eq!(moved.to_row_col(), Some(RowCol { row: 2, col: 0 }));
+        moved -= Direction::Down.offset() * 3;
+        assert_eq!(moved.to_row_col(), None);
+        assert!(RowCol::try_from(moved).is_err());
+        assert_eq!(Pos::from(RowCol { row: 1, col: 5 }), Pos::new(1, 5));
+
+        let grid = Vec2d::from_lines(["abc", "def"].into_iter()).unwrap();
+        assert_eq!(grid.get_pos(&Pos::new(1, 2)), Some(&'f'));
+        assert_eq!(grid.get_pos(&Pos::new(-1, 2)), None);
+        assert_eq!(Direction::Up.next(RowCol { row: 0, col: 1 }), None);
+        assert_eq!(
+            Direction::Left.next(RowCol { row: 0, col: 1 }),
+            Some(RowCol { row: 0, col: 0 })
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let grid = Vec2d::with_shape_and_value(0, 0, ' ');
+        assert_eq!((grid.num_rows(), grid.num_cols()), (0, 0));
+        assert!(grid.top_left_cell().is_none());
+        assert!(grid.bottom_right_cell().is_none());
+        assert_eq!(grid.cells().count(), 0);
+    }
+
+    #[test]
+    fn test_shape_errors() {
+        assert_eq!(
+            Vec2d::from_lines(std::iter::empty::<&str>()).unwrap_err(),
+            ShapeError::Empty
+        );
+        assert_eq!(
+            Vec2d::from_lines(["", ""].into_iter()).unwrap_err(),
+            ShapeError::EmptyRow { row: 0 }
+        );
+        let err = Vec2d::from_lines(["abc", "def", "gh"].into_iter()).unwrap_err();
+        assert_eq!(
+            err,
+            ShapeError::Ragged {
+                row: 2,
+                len: 2,
+                expected: 3
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "row 2 has length 2, but the first row has length 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_tiles() {
+        #[derive(Debug, PartialEq)]
+        struct Wall(bool);
+
+        impl TryFrom<char> for Wall {
+            type Error = anyhow::Error;
+
+            fn try_from(value: char) -> Result<Self, Self::Error> {
+                match value {
+                    '#' => Ok(Wall(true)),
+                    '.' => Ok(Wall(false)),
+                    _ => Err(anyhow::Error::msg("Invalid char received").context(value)),
+                }
+            }
+        }
+
+        let grid = Vec2d::from_lines(["#.", ".#"].into_iter()).unwrap();
+        let walls = grid.parse_tiles::<Wall>().unwrap();
+        assert_eq!(walls.get(1, 1), Some(&Wall(true)));
+
+        let grid = Vec2d::from_lines(["#.", ".x"].into_iter()).unwrap();
+        let err = grid.parse_tiles::<Wall>().unwrap_err();
+        assert_eq!(err.coords, RowCol { row: 1, col: 1 });
+        assert_eq!(err.to_string(), "unknown tile 'x' at row 1, column 1");
+    }
+
+    #[test]
+    fn test_direction_turns_and_reflections() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.reflect_forward().reflect_forward(), direction);
+            assert_eq!(direction.reflect_backward().reflect_backward(), direction);
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        // A beam moving right hits `/` and goes up, and hits `\` and goes down
+        assert_eq!(Direction::Right.reflect_forward(), Direction::Up);
+        assert_eq!(Direction::Right.reflect_backward(), Direction::Down);
+        assert_eq!(Direction::Down.reflect_forward(), Direction::Left);
+        assert!(Direction::Down.is_vertical());
+        assert!(!Direction::Left.is_vertical());
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!(Direction::try_from('^').unwrap(), Direction::Up);
+        assert_eq!(Direction::try_from('v').unwrap(), Direction::Down);
+        assert_eq!(Direction::try_from('W').unwrap(), Direction::Left);
+        assert_eq!("R".parse::<Direction>().unwrap(), Direction::Right);
+        assert!(Direction::try_from('x').is_err());
+        assert!("RR".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_eight_way_neighbors() {
+        let grid = Vec2d::from_lines(["abc", "def", "ghi"].into_iter()).unwrap();
+        let center = grid.get_cell(1, 1).unwrap();
+        let neighbors: Vec<_> = center
+            .neighbors_with_direction()
+            .map(|(direction, cell)| (direction, *cell.value()))
+            .collect();
+        assert_eq!(neighbors[0], (Direction8::Up, 'b'));
+        assert_eq!(neighbors[1], (Direction8::UpRight, 'c'));
+        assert_eq!(neighbors[7], (Direction8::UpLeft, 'a'));
+        assert_eq!(neighbors.len(), 8);
+        assert_eq!(
+            center
+                .neighbors()
+                .map(|cell| *cell.value())
+                .collect::<String>(),
+            "adgbhcfi"
+        );
+
+        let corner = grid.get_cell(0, 0).unwrap();
+        assert_eq!(
+            corner
+                .cardinal_neighbors_with_direction()
+                .map(|(direction, _)| direction)
+                .collect::<Vec<_>>(),
+            vec![Direction::Right, Direction::Down]
+        );
+        assert_eq!(
+            corner
+                .get_offset(Offset::new(2, 1))
+                .map(|cell| *cell.value()),
+            Some('h')
+        );
+
+        for direction in Direction8::ALL {
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(
+                direction.cardinal().map(Direction8::from),
+                (!direction.is_diagonal()).then_some(direction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let walls = Vec2d::from_lines(["#..", ".#.", "..#"].into_iter())
+            .unwrap()
+            .map(|&c| c == '#');
+        let mut bits = BitGrid::from(&walls);
+        assert_eq!(bits.count_ones(), 3);
+        assert!(bits.contains(&RowCol { row: 1, col: 1 }));
+        assert!(!bits.contains(&RowCol { row: 1, col: 3 }));
+        assert_eq!(bits.get(3, 0), None);
+        assert_eq!(bits.to_vec2d(), walls);
+
+        assert!(bits.set(&RowCol { row: 0, col: 0 }, false));
+        assert!(!bits.set(&RowCol { row: 0, col: 2 }, true));
+        assert_eq!(format!("{:?}", bits), "..#\n.#.\n..#\n");
+
+        let right = bits.shifted(Direction::Right);
+        assert_eq!(format!("{:?}", right), "...\n..#\n...\n");
+        let up = bits.shifted(Direction::Up);
+        assert_eq!(format!("{:?}", up), ".#.\n..#\n...\n");
+        let mut both = up.clone();
+        both.intersect_with(&right);
+        assert_eq!(both.ones().collect::<Vec<_>>(), [RowCol { row: 1, col: 2 }]);
+        both.union_with(&bits.shifted(Direction::Down));
+        assert_eq!(format!("{:?}", both), "...\n..#\n.#.\n");
+        both.difference_with(&right);
+        assert_eq!(both.count_ones(), 1);
+
+        both.clear();
+        assert!(both.is_empty());
+        both.shift_from(&bits, Direction::Left);
+        assert_eq!(format!("{:?}", both), ".#.\n#..\n.#.\n");
+    }
+
+    #[test]
+    fn test_bit_grid_shift_across_words() {
+        let mut bits = BitGrid::new(2, 130);
+        bits.set(&RowCol { row: 0, col: 63 }, true);
+        bits.set(&RowCol { row: 1, col: 129 }, true);
+        let right = bits.shifted(Direction::Right);
+        assert_eq!(
+            right.ones().collect::<Vec<_>>(),
+            [RowCol { row: 0, col: 64 }]
+        );
+        let left = right.shifted(Direction::Left).shifted(Direction::Left);
+        assert_eq!(
+            left.ones().collect::<Vec<_>>(),
+            [RowCol { row: 0, col: 62 }]
+        );
+        let down = bits.shifted(Direction::Down);
+        assert_eq!(
+            down.ones().collect::<Vec<_>>(),
+            [RowCol { row: 1, col: 63 }]
+        );
+        assert_eq!(BitGrid::new(0, 5).shifted(Direction::Up).count_ones(), 0);
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid: SparseGrid<char> = [
+            (Pos::new(-2, 3), 'a'),
+            (Pos::new(-1, 3), 'b'),
+            (Pos::new(0, 0), 'c'),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                top_left: Pos::new(-2, 0),
+                bottom_right: Pos::new(0, 3),
+            })
+        );
+        assert_eq!(grid.insert(Pos::new(0, 0), 'd'), Some('c'));
+        assert_eq!(grid.get(&Pos::new(0, 0)), Some(&'d'));
+
+        let cell = grid.get_cell(Pos::new(-2, 3)).unwrap();
+        assert_eq!(*cell.value(), 'a');
+        assert_eq!(
+            cell.get_next(Direction::Down).map(|cell| *cell.value()),
+            Some('b')
+        );
+        assert!(cell.get_next(Direction::Up).is_none());
+        assert_eq!(cell.cardinal_neighbors().count(), 1);
+        assert_eq!(
+            grid.get_cell(Pos::new(-1, 3)).unwrap().neighbors().count(),
+            1
+        );
+
+        let dense = grid.to_vec2d('.');
+        assert_eq!(
+            dense.rows().collect::<Vec<_>>(),
+            [
+                &['.', '.', '.', 'a'],
+                &['.', '.', '.', 'b'],
+                &['d', '.', '.', '.'],
+            ]
+        );
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(
+            bounds.to_row_col(&Pos::new(-1, 3)),
+            Some(RowCol { row: 1, col: 3 })
+        );
+        assert_eq!(bounds.to_pos(&RowCol { row: 2, col: 0 }), Pos::new(0, 0));
+        assert_eq!(bounds.to_row_col(&Pos::new(1, 0)), None);
+
+        assert_eq!(grid.remove(&Pos::new(-2, 3)), Some('a'));
+        assert_eq!(grid.bounds().unwrap().top_left, Pos::new(-1, 0));
+        grid.remove(&Pos::new(-1, 3));
+        grid.remove(&Pos::new(0, 0));
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_vec2d('.').num_rows(), 0);
+    }
+
+    #[test]
+    fn test_ray_casting() {
+        let grid = Vec2d::from_lines(["#.#..", "1.2.3", "4..#5", "6.789"].into_iter()).unwrap();
+        let cell = grid.get_cell(1, 2).unwrap();
+        let values = |direction| {
+            cell.ray(direction)
+                .map(|cell| *cell.value())
+                .collect::<String>()
+        };
+        assert_eq!(values(Direction::Up), "#");
+        assert_eq!(values(Direction::Down), ".7");
+        assert_eq!(values(Direction::Left), ".1");
+        assert_eq!(values(Direction::Right), ".3");
+
+        let corner = grid.get_cell(0, 0).unwrap();
+        assert_eq!(corner.ray(Direction::Up).count(), 0);
+        assert_eq!(
+            corner
+                .find_first_in(Direction::Down, |c| c.is_ascii_digit())
+                .map(|cell| cell.coords()),
+            Some(RowCol { row: 1, col: 0 })
+        );
+        assert!(corner
+            .find_first_in(Direction::Right, |&c| c == '3')
+            .is_none());
+        assert_eq!(
+            corner
+                .find_last_satisfying_in(Direction::Right, |&c| c != '#')
+                .coords(),
+            RowCol { row: 0, col: 1 }
+        );
+        let top = grid.get_cell(0, 1).unwrap();
+        assert_eq!(
+            top.find_last_satisfying_in(Direction::Down, |&c| c == '.')
+                .coords(),
+            RowCol { row: 3, col: 1 }
+        );
+        assert!(top.find_last_satisfying_in(Direction::Left, |&c| c == '.') == top);
+    }
+
+    #[test]
+    fn test_contiguous_ranges() {
+        let grid = Vec2d::from_lines(["1.2", "34.", "5#6", "78."].into_iter()).unwrap();
+        let cell = grid.get_cell(1, 0).unwrap();
+        let row = cell.find_contiguous_satisfying(|c| c.is_ascii_digit());
+        assert_eq!(row.as_slice(), &['3', '4']);
+
+        let col = cell.find_contiguous_satisfying_in_col(|c| c.is_ascii_digit());
+        assert_eq!(col.first().coords(), RowCol { row: 0, col: 0 });
+        assert_eq!(col.last().coords(), RowCol { row: 3, col: 0 });
+        assert_eq!(col.values().collect::<String>(), "1357");
+        let col = grid
+            .get_cell(3, 1)
+            .unwrap()
+            .find_contiguous_satisfying_in_col(|c| c.is_ascii_digit());
+        assert_eq!(
+            col.cells().map(|cell| cell.coords()).collect::<Vec<_>>(),
+            [RowCol { row: 3, col: 1 }]
+        );
+    }
 }
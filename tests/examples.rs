@@ -0,0 +1,43 @@
+//! Checks the solutions against the example files in `tests/examples`.
+//!
+//! Each `tests/examples/dayN/<name>.txt` file becomes a test named `dayNN_<name>`, generated by
+//! `build.rs`. See [`Example`] for the format of the files.
+
+use std::fs;
+
+use advent_of_code_2023_solutions::example::Example;
+use advent_of_code_2023_solutions::params::Params;
+use advent_of_code_2023_solutions::registry;
+
+fn check_example(day: u8, path: &str) {
+    let contents = fs::read_to_string(path).unwrap_or_else(|err| panic!("{}: {}", path, err));
+    let example: Example = contents
+        .parse()
+        .unwrap_or_else(|err| panic!("{}: {:#}", path, err));
+    for (part, expected) in &example.answers {
+        let registration =
+            registry::find(day, *part).unwrap_or_else(|err| panic!("{}: {}", path, err));
+        // Only pass the parameters this part uses, so one file can set parameters for both parts
+        let mut params = Params::default();
+        for assignment in &example.params {
+            params
+                .set_from_str(assignment)
+                .unwrap_or_else(|err| panic!("{}: {:#}", path, err));
+        }
+        let names: Vec<_> = registration.params().map(|param| param.name).collect();
+        params.retain(|name| names.contains(&name));
+        let answer = registration
+            .solve(&example.input, &params)
+            .unwrap_or_else(|err| panic!("{}: {}", path, err));
+        assert!(
+            example.matches(*part, &answer),
+            "{}: part {}: expected {}, got {}",
+            path,
+            part,
+            expected,
+            answer
+        );
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));